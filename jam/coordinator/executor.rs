@@ -0,0 +1,37 @@
+//! Pluggable executors for JAM operation types
+//!
+//! Each `JamOperationType` is handled by an executor registered with the
+//! `JamCoordinator`. Downstream crates add domain-specific operations by
+//! registering an executor for a `JamOperationType::Custom` code.
+
+use sp_std::prelude::*;
+use sp_runtime::traits::Hash;
+
+use crate::{CoordinatedOperation, JamError};
+
+/// Result of handing an operation to its executor
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum JamExecution {
+    /// The operation took effect and is completed
+    Completed,
+    /// The operation was dispatched and stays in progress until its
+    /// completion or failure is reported to the coordinator
+    Dispatched,
+}
+
+/// Validation and execution handler for one JAM operation type
+pub trait JamExecutor<AccountId, BlockNumber, H: Hash> {
+    /// Check an operation before the coordinator accepts it
+    fn validate(&self, _operation: &CoordinatedOperation<AccountId, BlockNumber, H>) -> Result<(), JamError> {
+        Ok(())
+    }
+
+    /// Execute an operation whose proofs have been verified
+    fn execute(
+        &mut self,
+        operation: &CoordinatedOperation<AccountId, BlockNumber, H>,
+    ) -> Result<JamExecution, JamError>;
+}
+
+/// Boxed executor as stored by the coordinator
+pub type BoxedExecutor<AccountId, BlockNumber, H> = Box<dyn JamExecutor<AccountId, BlockNumber, H>>;
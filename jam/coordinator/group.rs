@@ -0,0 +1,615 @@
+//! Atomic two-phase commit for multi-parachain JAM operations
+//!
+//! A group operation spans several parachains, one leg per parachain. Every
+//! leg is first asked to prepare and votes on whether it can apply its part.
+//! The group commits once every leg has prepared, and aborts as soon as any
+//! leg refuses or when `expires_at` is reached before all votes are in. The
+//! decision is final, so participants either all commit or all roll back.
+//! On commit every leg is handed to its executor as an ordinary coordinated
+//! operation and follows the usual operation lifecycle from then on.
+
+use sp_std::prelude::*;
+use sp_runtime::traits::Hash;
+use scale_info::TypeInfo;
+use codec::{Encode, Decode, MaxEncodedLen};
+
+use crate::executor::JamExecution;
+use crate::{CoordinatedOperation, JamCoordinator, JamError, JamOperation, JamOperationStatus, JamOperationType};
+
+/// Vote of one group leg in the prepare phase
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+pub enum JamVote {
+    /// No vote received yet
+    Pending,
+    /// The leg is prepared and ready to commit
+    Prepared,
+    /// The leg cannot be applied
+    Refused,
+}
+
+/// Status of a group operation
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+pub enum JamGroupStatus {
+    /// Collecting prepare votes
+    Preparing,
+    /// Every leg prepared; all legs must commit
+    Committed,
+    /// A leg refused or the group expired; all legs must roll back
+    Aborted,
+}
+
+/// One parachain's part of a group operation
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo)]
+pub struct JamGroupLeg {
+    /// Target parachain ID
+    pub target_parachain_id: u32,
+    /// Operation type
+    pub operation_type: JamOperationType,
+    /// Operation data
+    pub data: Vec<u8>,
+    /// Prepare vote of the target parachain
+    pub vote: JamVote,
+}
+
+/// JAM operation spanning several parachains
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo)]
+pub struct JamGroupOperation<AccountId, BlockNumber> {
+    /// Initiator of the operation
+    pub initiator: AccountId,
+    /// Legs, one per target parachain
+    pub legs: Vec<JamGroupLeg>,
+    /// Creation block number
+    pub created_at: BlockNumber,
+    /// Block number at which an undecided group aborts
+    pub expires_at: BlockNumber,
+    /// Group status
+    pub status: JamGroupStatus,
+}
+
+impl<AccountId, BlockNumber, H: Hash> JamCoordinator<AccountId, BlockNumber, H>
+where
+    AccountId: Encode + PartialEq + Clone,
+    BlockNumber: Encode + Ord + Copy,
+    H::Output: Ord,
+{
+    /// Submit a new group operation
+    ///
+    /// Every leg must target a different parachain and have an executor
+    /// registered for its operation type. A leg may not match an existing
+    /// operation or a leg of another undecided group, so no leg can be
+    /// dispatched twice. Returns the group hash.
+    pub fn submit_group(
+        &mut self,
+        group: JamGroupOperation<AccountId, BlockNumber>,
+        now: BlockNumber,
+    ) -> Result<H::Output, JamError> {
+        if group.status != JamGroupStatus::Preparing || group.legs.iter().any(|leg| leg.vote != JamVote::Pending) {
+            return Err(JamError::NotPending);
+        }
+        if group.legs.is_empty() {
+            return Err(JamError::EmptyGroup);
+        }
+        let mut parachains: Vec<u32> = group.legs.iter().map(|leg| leg.target_parachain_id).collect();
+        parachains.sort_unstable();
+        if parachains.windows(2).any(|w| w[0] == w[1]) {
+            return Err(JamError::DuplicateParticipant);
+        }
+        if group.expires_at <= now {
+            return Err(JamError::OperationExpired);
+        }
+
+        let hash = <H as Hash>::hash_of(&group);
+        if self.groups.contains_key(&hash) {
+            return Err(JamError::OperationAlreadyExists);
+        }
+        if self.active_count() >= self.max_active_operations as usize {
+            return Err(JamError::TooManyOperations);
+        }
+        let reserved: Vec<H::Output> = self
+            .groups
+            .values()
+            .filter(|other| other.status == JamGroupStatus::Preparing)
+            .flat_map(|other| other.legs.iter().map(move |leg| Self::leg_operation_hash(other, leg)))
+            .collect();
+        for leg in &group.legs {
+            let operation = Self::leg_operation(&group, leg);
+            let leg_hash = <H as Hash>::hash_of(&operation);
+            if self.operations.contains_key(&leg_hash) || reserved.contains(&leg_hash) {
+                return Err(JamError::OperationAlreadyExists);
+            }
+            self.executors
+                .get(&leg.operation_type)
+                .ok_or(JamError::UnsupportedOperationType)?
+                .validate(&operation)?;
+        }
+
+        self.groups.insert(hash, group);
+        Ok(hash)
+    }
+
+    /// Record the prepare vote of `parachain_id` for a group
+    ///
+    /// Returns the group status after the vote. A vote arriving at or after
+    /// `expires_at` aborts the group instead of being counted. The vote that
+    /// commits the group dispatches every leg to its executor.
+    pub fn record_vote(
+        &mut self,
+        hash: &H::Output,
+        parachain_id: u32,
+        prepared: bool,
+        now: BlockNumber,
+    ) -> Result<JamGroupStatus, JamError> {
+        let group = self.groups.get_mut(hash).ok_or(JamError::OperationNotFound)?;
+        if group.status != JamGroupStatus::Preparing {
+            return Err(JamError::GroupDecided);
+        }
+        if group.expires_at <= now {
+            group.status = JamGroupStatus::Aborted;
+            return Ok(JamGroupStatus::Aborted);
+        }
+
+        let leg = group
+            .legs
+            .iter_mut()
+            .find(|leg| leg.target_parachain_id == parachain_id)
+            .ok_or(JamError::NotAParticipant)?;
+        if leg.vote != JamVote::Pending {
+            return Err(JamError::AlreadyVoted);
+        }
+        leg.vote = if prepared { JamVote::Prepared } else { JamVote::Refused };
+
+        if !prepared {
+            group.status = JamGroupStatus::Aborted;
+        } else if group.legs.iter().all(|leg| leg.vote == JamVote::Prepared) {
+            group.status = JamGroupStatus::Committed;
+        }
+
+        let status = group.status;
+        if status == JamGroupStatus::Committed {
+            self.dispatch_legs(hash);
+        }
+        Ok(status)
+    }
+
+    /// Abort every undecided group whose `expires_at` has been reached
+    ///
+    /// Returns the hashes of the aborted groups.
+    pub fn expire_groups(&mut self, now: BlockNumber) -> Vec<H::Output> {
+        self.groups
+            .iter_mut()
+            .filter(|(_, group)| group.status == JamGroupStatus::Preparing && group.expires_at <= now)
+            .map(|(hash, group)| {
+                group.status = JamGroupStatus::Aborted;
+                *hash
+            })
+            .collect()
+    }
+
+    /// Group operation by hash
+    pub fn group(&self, hash: &H::Output) -> Option<&JamGroupOperation<AccountId, BlockNumber>> {
+        self.groups.get(hash)
+    }
+
+    /// Hashes of the groups with the given status, in ascending order
+    pub fn groups_by_status(&self, status: JamGroupStatus) -> Vec<H::Output> {
+        self.groups
+            .iter()
+            .filter(|(_, group)| group.status == status)
+            .map(|(hash, _)| *hash)
+            .collect()
+    }
+
+    /// Hashes of the operations carrying out the legs of a committed group,
+    /// in leg order
+    ///
+    /// Undecided and aborted groups have no leg operations.
+    pub fn group_leg_operations(&self, hash: &H::Output) -> Vec<H::Output> {
+        match self.groups.get(hash) {
+            Some(group) if group.status == JamGroupStatus::Committed => group
+                .legs
+                .iter()
+                .map(|leg| Self::leg_operation_hash(group, leg))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Remove all decided groups and return them
+    pub fn prune_decided_groups(&mut self) -> Vec<(H::Output, JamGroupOperation<AccountId, BlockNumber>)> {
+        let decided: Vec<H::Output> = self
+            .groups
+            .iter()
+            .filter(|(_, group)| group.status != JamGroupStatus::Preparing)
+            .map(|(hash, _)| *hash)
+            .collect();
+
+        decided
+            .into_iter()
+            .filter_map(|hash| self.groups.remove(&hash).map(|group| (hash, group)))
+            .collect()
+    }
+
+    /// Coordinated operation carrying out one leg of a group
+    fn leg_operation(
+        group: &JamGroupOperation<AccountId, BlockNumber>,
+        leg: &JamGroupLeg,
+    ) -> CoordinatedOperation<AccountId, BlockNumber, H> {
+        JamOperation {
+            initiator: group.initiator.clone(),
+            target_parachain_id: leg.target_parachain_id,
+            operation_type: leg.operation_type,
+            data: leg.data.clone(),
+            created_at: group.created_at,
+            expires_at: group.expires_at,
+            proofs: Vec::new(),
+            status: JamOperationStatus::InProgress,
+        }
+    }
+
+    /// Hash of the coordinated operation carrying out one leg of a group
+    fn leg_operation_hash(group: &JamGroupOperation<AccountId, BlockNumber>, leg: &JamGroupLeg) -> H::Output {
+        <H as Hash>::hash_of(&Self::leg_operation(group, leg))
+    }
+
+    /// Hand every leg of a committed group to its executor
+    ///
+    /// Each leg is added as an in-progress operation. Legs the executor
+    /// completes are recorded in the history and failing legs are marked
+    /// failed; dispatched legs stay in progress until reported. A leg that is
+    /// already an operation is never dispatched again.
+    fn dispatch_legs(&mut self, hash: &H::Output) {
+        let operations: Vec<_> = match self.groups.get(hash) {
+            Some(group) => group.legs.iter().map(|leg| Self::leg_operation(group, leg)).collect(),
+            None => return,
+        };
+
+        for operation in operations {
+            let leg_hash = <H as Hash>::hash_of(&operation);
+            if self.operations.contains_key(&leg_hash) {
+                continue;
+            }
+            self.operations.insert(leg_hash, operation);
+
+            let status = match self.dispatch(&leg_hash) {
+                Ok(JamExecution::Completed) => JamOperationStatus::Completed,
+                Ok(JamExecution::Dispatched) => continue,
+                Err(_) => JamOperationStatus::Failed,
+            };
+            if self.set_status(&leg_hash, status).is_ok() && status == JamOperationStatus::Completed {
+                self.record_completion(&leg_hash);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::{BoxedExecutor, JamExecution, JamExecutor};
+    use crate::CoordinatedOperation;
+    use sp_runtime::testing::H256;
+    use sp_runtime::traits::BlakeTwo256;
+    use sp_std::cell::RefCell;
+    use sp_std::collections::btree_map::BTreeMap;
+    use sp_std::rc::Rc;
+
+    type TestCoordinator = JamCoordinator<u64, u32>;
+
+    struct NoopExecutor;
+
+    impl JamExecutor<u64, u32, BlakeTwo256> for NoopExecutor {
+        fn execute(&mut self, _operation: &CoordinatedOperation<u64, u32, BlakeTwo256>) -> Result<JamExecution, JamError> {
+            Ok(JamExecution::Dispatched)
+        }
+    }
+
+    /// Executor recording the target parachain of every executed operation
+    struct RecordingExecutor {
+        executed: Rc<RefCell<Vec<u32>>>,
+        result: Result<JamExecution, JamError>,
+    }
+
+    impl JamExecutor<u64, u32, BlakeTwo256> for RecordingExecutor {
+        fn execute(&mut self, operation: &CoordinatedOperation<u64, u32, BlakeTwo256>) -> Result<JamExecution, JamError> {
+            self.executed.borrow_mut().push(operation.target_parachain_id);
+            self.result
+        }
+    }
+
+    fn recording_coordinator(result: Result<JamExecution, JamError>) -> (TestCoordinator, Rc<RefCell<Vec<u32>>>) {
+        let executed = Rc::new(RefCell::new(Vec::new()));
+        let mut coordinator = TestCoordinator::new();
+        let executor: BoxedExecutor<u64, u32, BlakeTwo256> =
+            Box::new(RecordingExecutor { executed: executed.clone(), result });
+        coordinator.register_executor(JamOperationType::AssetTransfer, executor).unwrap();
+        (coordinator, executed)
+    }
+
+    /// Simulated parachain holding balances, with a prepare lock per group
+    #[derive(Default)]
+    struct SimulatedChain {
+        balance: u64,
+        locked: BTreeMap<H256, u64>,
+        offline: bool,
+    }
+
+    impl SimulatedChain {
+        fn new(balance: u64) -> Self {
+            Self { balance, ..Default::default() }
+        }
+
+        /// Lock the debit of a leg, returning the prepare vote
+        fn prepare(&mut self, group: H256, leg: &JamGroupLeg) -> Option<bool> {
+            if self.offline {
+                return None;
+            }
+            let amount = u64::decode(&mut &leg.data[..]).unwrap();
+            let available = self.balance - self.locked.values().sum::<u64>();
+            if amount > available {
+                return Some(false);
+            }
+            self.locked.insert(group, amount);
+            Some(true)
+        }
+
+        fn finish(&mut self, group: H256, status: JamGroupStatus) {
+            let amount = self.locked.remove(&group).unwrap_or_default();
+            if status == JamGroupStatus::Committed {
+                self.balance -= amount;
+            }
+        }
+    }
+
+    fn coordinator() -> TestCoordinator {
+        let mut coordinator = TestCoordinator::new();
+        let executor: BoxedExecutor<u64, u32, BlakeTwo256> = Box::new(NoopExecutor);
+        coordinator.register_executor(JamOperationType::AssetTransfer, executor).unwrap();
+        coordinator
+    }
+
+    fn group(debits: &[(u32, u64)], expires_at: u32) -> JamGroupOperation<u64, u32> {
+        JamGroupOperation {
+            initiator: 1,
+            legs: debits
+                .iter()
+                .map(|(parachain, amount)| JamGroupLeg {
+                    target_parachain_id: *parachain,
+                    operation_type: JamOperationType::AssetTransfer,
+                    data: amount.encode(),
+                    vote: JamVote::Pending,
+                })
+                .collect(),
+            created_at: 1,
+            expires_at,
+            status: JamGroupStatus::Preparing,
+        }
+    }
+
+    /// Run the prepare phase against the chains, then deliver the decision
+    fn run(
+        coordinator: &mut TestCoordinator,
+        chains: &mut BTreeMap<u32, SimulatedChain>,
+        hash: H256,
+        now: u32,
+    ) -> JamGroupStatus {
+        let legs = coordinator.group(&hash).unwrap().legs.clone();
+        for leg in &legs {
+            let chain = chains.get_mut(&leg.target_parachain_id).unwrap();
+            if let Some(vote) = chain.prepare(hash, leg) {
+                if coordinator.record_vote(&hash, leg.target_parachain_id, vote, now) != Ok(JamGroupStatus::Preparing) {
+                    break;
+                }
+            }
+        }
+        coordinator.expire_groups(now);
+
+        let status = coordinator.group(&hash).unwrap().status;
+        if status != JamGroupStatus::Preparing {
+            for chain in chains.values_mut() {
+                chain.finish(hash, status);
+            }
+        }
+        status
+    }
+
+    fn chains(balances: &[(u32, u64)]) -> BTreeMap<u32, SimulatedChain> {
+        balances.iter().map(|(id, balance)| (*id, SimulatedChain::new(*balance))).collect()
+    }
+
+    fn balances(chains: &BTreeMap<u32, SimulatedChain>) -> Vec<u64> {
+        chains.values().map(|chain| chain.balance).collect()
+    }
+
+    #[test]
+    fn test_all_legs_commit() {
+        let mut coordinator = coordinator();
+        let mut chains = chains(&[(2000, 100), (2001, 100), (2002, 100)]);
+        let hash = coordinator.submit_group(group(&[(2000, 10), (2001, 20), (2002, 30)], 10), 1).unwrap();
+
+        assert_eq!(run(&mut coordinator, &mut chains, hash, 2), JamGroupStatus::Committed);
+        assert_eq!(balances(&chains), vec![90, 80, 70]);
+
+        let mut legs = coordinator.group_leg_operations(&hash);
+        assert_eq!(legs.len(), 3);
+        legs.sort();
+        assert_eq!(coordinator.operations_by_status(JamOperationStatus::InProgress), legs);
+    }
+
+    #[test]
+    fn test_commit_dispatches_every_leg() {
+        let (mut coordinator, executed) = recording_coordinator(Ok(JamExecution::Completed));
+        let hash = coordinator.submit_group(group(&[(2000, 10), (2001, 20)], 10), 1).unwrap();
+
+        assert_eq!(coordinator.record_vote(&hash, 2001, true, 2), Ok(JamGroupStatus::Preparing));
+        assert!(executed.borrow().is_empty());
+        assert_eq!(coordinator.record_vote(&hash, 2000, true, 2), Ok(JamGroupStatus::Committed));
+        assert_eq!(*executed.borrow(), vec![2000, 2001]);
+
+        let legs = coordinator.group_leg_operations(&hash);
+        for (leg, parachain) in legs.iter().zip([2000, 2001]) {
+            let operation = coordinator.operation(leg).unwrap();
+            assert_eq!(operation.target_parachain_id, parachain);
+            assert_eq!(operation.status, JamOperationStatus::Completed);
+            assert!(coordinator.history_proof(leg).is_some());
+        }
+
+        // The legs cannot be dispatched a second time by an identical group
+        coordinator.prune_decided_groups();
+        assert_eq!(
+            coordinator.submit_group(group(&[(2000, 10), (2001, 20)], 10), 1),
+            Err(JamError::OperationAlreadyExists)
+        );
+    }
+
+    #[test]
+    fn test_groups_sharing_legs_are_rejected() {
+        let (mut coordinator, executed) = recording_coordinator(Ok(JamExecution::Completed));
+        let first = coordinator.submit_group(group(&[(2000, 10), (2001, 20)], 10), 1).unwrap();
+
+        // The same legs in another order form a different group
+        let reordered = group(&[(2001, 20), (2000, 10)], 10);
+        assert_ne!(BlakeTwo256::hash_of(&reordered), first);
+        assert_eq!(coordinator.submit_group(reordered, 1), Err(JamError::OperationAlreadyExists));
+        assert_eq!(
+            coordinator.submit_group(group(&[(2002, 5), (2001, 20)], 10), 1),
+            Err(JamError::OperationAlreadyExists)
+        );
+
+        coordinator.record_vote(&first, 2000, true, 2).unwrap();
+        coordinator.record_vote(&first, 2001, true, 2).unwrap();
+        assert_eq!(*executed.borrow(), vec![2000, 2001]);
+        assert_eq!(coordinator.history().leaf_count(), 2);
+
+        // Once the group is committed its legs are operations
+        assert_eq!(
+            coordinator.submit_group(group(&[(2001, 20), (2000, 10)], 10), 1),
+            Err(JamError::OperationAlreadyExists)
+        );
+
+        // An aborted group releases its legs
+        let aborted = coordinator.submit_group(group(&[(2002, 5)], 10), 1).unwrap();
+        coordinator.record_vote(&aborted, 2002, false, 2).unwrap();
+        let retried = coordinator.submit_group(group(&[(2003, 1), (2002, 5)], 10), 1).unwrap();
+        coordinator.record_vote(&retried, 2002, true, 3).unwrap();
+        coordinator.record_vote(&retried, 2003, true, 3).unwrap();
+        assert_eq!(*executed.borrow(), vec![2000, 2001, 2003, 2002]);
+        assert_eq!(coordinator.history().leaf_count(), 4);
+    }
+
+    #[test]
+    fn test_failing_legs_are_marked_failed() {
+        let (mut coordinator, executed) = recording_coordinator(Err(JamError::Custom(1)));
+        let hash = coordinator.submit_group(group(&[(2000, 10), (2001, 20)], 10), 1).unwrap();
+        coordinator.record_vote(&hash, 2000, true, 2).unwrap();
+        coordinator.record_vote(&hash, 2001, true, 2).unwrap();
+
+        assert_eq!(executed.borrow().len(), 2);
+        let mut legs = coordinator.group_leg_operations(&hash);
+        legs.sort();
+        assert_eq!(coordinator.operations_by_status(JamOperationStatus::Failed), legs);
+    }
+
+    #[test]
+    fn test_aborted_groups_dispatch_nothing() {
+        let (mut coordinator, executed) = recording_coordinator(Ok(JamExecution::Completed));
+        let refused = coordinator.submit_group(group(&[(2000, 10), (2001, 20)], 10), 1).unwrap();
+        coordinator.record_vote(&refused, 2000, true, 2).unwrap();
+        assert_eq!(coordinator.record_vote(&refused, 2001, false, 2), Ok(JamGroupStatus::Aborted));
+
+        let expired = coordinator.submit_group(group(&[(2002, 10)], 5), 1).unwrap();
+        assert_eq!(coordinator.record_vote(&expired, 2002, true, 5), Ok(JamGroupStatus::Aborted));
+
+        assert!(executed.borrow().is_empty());
+        assert!(coordinator.group_leg_operations(&refused).is_empty());
+        assert!(coordinator.operations_by_status(JamOperationStatus::InProgress).is_empty());
+    }
+
+    #[test]
+    fn test_refusal_aborts_every_leg() {
+        let mut coordinator = coordinator();
+        let mut chains = chains(&[(2000, 100), (2001, 5), (2002, 100)]);
+        let hash = coordinator.submit_group(group(&[(2000, 10), (2001, 20), (2002, 30)], 10), 1).unwrap();
+
+        assert_eq!(run(&mut coordinator, &mut chains, hash, 2), JamGroupStatus::Aborted);
+        assert_eq!(balances(&chains), vec![100, 5, 100]);
+        assert!(chains.values().all(|chain| chain.locked.is_empty()));
+        assert_eq!(coordinator.record_vote(&hash, 2002, true, 2), Err(JamError::GroupDecided));
+    }
+
+    #[test]
+    fn test_timeout_aborts_prepared_legs() {
+        let mut coordinator = coordinator();
+        let mut chains = chains(&[(2000, 100), (2001, 100)]);
+        chains.get_mut(&2001).unwrap().offline = true;
+        let hash = coordinator.submit_group(group(&[(2000, 10), (2001, 20)], 10), 1).unwrap();
+
+        assert_eq!(run(&mut coordinator, &mut chains, hash, 2), JamGroupStatus::Preparing);
+        assert_eq!(chains[&2000].locked.len(), 1);
+
+        assert_eq!(coordinator.expire_groups(9), Vec::<H256>::new());
+        assert_eq!(coordinator.expire_groups(10), vec![hash]);
+        for chain in chains.values_mut() {
+            chain.finish(hash, coordinator.group(&hash).unwrap().status);
+        }
+        assert_eq!(balances(&chains), vec![100, 100]);
+        assert!(chains.values().all(|chain| chain.locked.is_empty()));
+    }
+
+    #[test]
+    fn test_all_or_nothing_across_scenarios() {
+        for budget in 0..8u64 {
+            let mut coordinator = coordinator();
+            let mut chains = chains(&[(2000, budget), (2001, 7 - budget), (2002, 4)]);
+            let hash = coordinator.submit_group(group(&[(2000, 3), (2001, 3), (2002, 3)], 10), 1).unwrap();
+
+            let before = balances(&chains);
+            let status = run(&mut coordinator, &mut chains, hash, 2);
+            let after = balances(&chains);
+
+            let moved: Vec<bool> = before.iter().zip(&after).map(|(b, a)| b != a).collect();
+            match status {
+                JamGroupStatus::Committed => assert!(moved.iter().all(|m| *m)),
+                _ => assert!(moved.iter().all(|m| !*m)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_group_rejections() {
+        let mut coordinator = coordinator();
+        assert_eq!(coordinator.submit_group(group(&[], 10), 1), Err(JamError::EmptyGroup));
+        assert_eq!(
+            coordinator.submit_group(group(&[(2000, 1), (2000, 2)], 10), 1),
+            Err(JamError::DuplicateParticipant)
+        );
+        assert_eq!(coordinator.submit_group(group(&[(2000, 1)], 1), 1), Err(JamError::OperationExpired));
+
+        let mut unsupported = group(&[(2000, 1)], 10);
+        unsupported.legs[0].operation_type = JamOperationType::Custom(3);
+        assert_eq!(coordinator.submit_group(unsupported, 1), Err(JamError::UnsupportedOperationType));
+
+        let hash = coordinator.submit_group(group(&[(2000, 1), (2001, 1)], 10), 1).unwrap();
+        assert_eq!(
+            coordinator.submit_group(group(&[(2000, 1), (2001, 1)], 10), 1),
+            Err(JamError::OperationAlreadyExists)
+        );
+        assert_eq!(coordinator.record_vote(&hash, 2005, true, 2), Err(JamError::NotAParticipant));
+        coordinator.record_vote(&hash, 2000, true, 2).unwrap();
+        assert_eq!(coordinator.record_vote(&hash, 2000, true, 2), Err(JamError::AlreadyVoted));
+        assert_eq!(coordinator.record_vote(&hash, 2001, true, 10), Ok(JamGroupStatus::Aborted));
+        assert_eq!(coordinator.groups_by_status(JamGroupStatus::Aborted), vec![hash]);
+        assert_eq!(coordinator.record_vote(&hash, 2001, true, 10), Err(JamError::GroupDecided));
+        assert!(coordinator.group_leg_operations(&hash).is_empty());
+        assert_eq!(coordinator.prune_decided_groups().len(), 1);
+    }
+
+    #[test]
+    fn test_groups_count_towards_active_limit() {
+        let mut coordinator = TestCoordinator::with_max_active_operations(1);
+        let executor: BoxedExecutor<u64, u32, BlakeTwo256> = Box::new(NoopExecutor);
+        coordinator.register_executor(JamOperationType::AssetTransfer, executor).unwrap();
+
+        coordinator.submit_group(group(&[(2000, 1)], 10), 1).unwrap();
+        assert_eq!(coordinator.submit_group(group(&[(2000, 2)], 10), 1), Err(JamError::TooManyOperations));
+    }
+}
@@ -5,13 +5,31 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use sp_std::prelude::*;
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
 use sp_runtime::traits::{BlakeTwo256, Hash};
 use scale_info::TypeInfo;
 use codec::{Encode, Decode, MaxEncodedLen};
 
+pub mod executor;
+pub mod group;
+pub mod mmr;
+pub mod smt;
+
+use executor::{BoxedExecutor, JamExecution};
+use group::{JamGroupOperation, JamGroupStatus};
+use mmr::{MerkleMountainRange, MmrProof};
+
+/// Default maximum number of pending and in-progress operations
+pub const DEFAULT_MAX_ACTIVE_OPERATIONS: u32 = 1024;
+
+/// Domain separation prefix for Merkle leaf hashes
+pub const MERKLE_LEAF_PREFIX: u8 = 0x00;
+
+/// Domain separation prefix for Merkle internal node hashes
+pub const MERKLE_NODE_PREFIX: u8 = 0x01;
+
 /// JAM Proof structure representing a proof of inclusion
-#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo)]
 pub struct JamProof<BlockHash, MerkleHash> {
     /// Block hash that contains the Merkle root
     pub block_hash: BlockHash,
@@ -25,6 +43,29 @@ pub struct JamProof<BlockHash, MerkleHash> {
     pub justified_data: Vec<u8>,
 }
 
+/// JAM multiproof proving several leaves of one Merkle tree at once
+///
+/// Siblings shared between the proven leaves' paths, and siblings that are
+/// themselves computed from proven leaves, are included only once or not at
+/// all, which keeps batched justification far smaller than one `JamProof`
+/// per leaf.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo)]
+pub struct JamMultiProof<BlockHash, MerkleHash> {
+    /// Block hash that contains the Merkle root
+    pub block_hash: BlockHash,
+    /// Merkle root hash
+    pub merkle_root: MerkleHash,
+    /// Total number of leaves in the tree
+    pub leaf_count: u64,
+    /// Proven leaf indices, strictly increasing
+    pub leaf_indices: Vec<u64>,
+    /// Justified data for each proven leaf, in `leaf_indices` order
+    pub justified_data: Vec<Vec<u8>>,
+    /// Sibling hashes not derivable from the proven leaves, bottom-up and
+    /// left to right within each level
+    pub proof_hashes: Vec<MerkleHash>,
+}
+
 /// JAM Operation for cross-chain atomic actions
 #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo)]
 pub struct JamOperation<AccountId, BlockNumber, Hash> {
@@ -47,7 +88,7 @@ pub struct JamOperation<AccountId, BlockNumber, Hash> {
 }
 
 /// Types of JAM operations
-#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, TypeInfo, MaxEncodedLen)]
 pub enum JamOperationType {
     /// Cross-chain asset transfer
     AssetTransfer,
@@ -78,58 +119,1176 @@ pub enum JamOperationStatus {
     Expired,
 }
 
+impl JamOperationStatus {
+    /// Whether the operation has reached a final status
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Expired)
+    }
+
+    /// Whether the operation lifecycle allows moving to `to`
+    ///
+    /// Pending operations may start, fail or expire; in-progress operations
+    /// may complete, fail or expire. Final statuses never change.
+    pub fn can_transition_to(&self, to: JamOperationStatus) -> bool {
+        matches!(
+            (self, to),
+            (Self::Pending, Self::InProgress | Self::Failed | Self::Expired)
+                | (Self::InProgress, Self::Completed | Self::Failed | Self::Expired)
+        )
+    }
+}
+
+/// Errors returned by the JAM coordinator
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+pub enum JamError {
+    /// Operation not found
+    OperationNotFound,
+    /// Operation already submitted
+    OperationAlreadyExists,
+    /// Too many active operations
+    TooManyOperations,
+    /// Operation expired
+    OperationExpired,
+    /// Submitted operation is not pending
+    NotPending,
+    /// Operation proofs failed verification
+    InvalidProof,
+    /// No executor registered for the operation type
+    UnsupportedOperationType,
+    /// An executor is already registered for the operation type
+    ExecutorAlreadyRegistered,
+    /// Group operation has no legs
+    EmptyGroup,
+    /// Two group legs target the same parachain
+    DuplicateParticipant,
+    /// Parachain is not part of the group operation
+    NotAParticipant,
+    /// Parachain already voted on the group operation
+    AlreadyVoted,
+    /// Group operation was already committed or aborted
+    GroupDecided,
+    /// Status change not allowed by the operation lifecycle
+    InvalidTransition {
+        /// Current status
+        from: JamOperationStatus,
+        /// Requested status
+        to: JamOperationStatus,
+    },
+    /// Executor-defined validation or execution error
+    Custom(u8),
+}
+
+/// Binary Merkle tree over SCALE-encoded items
+///
+/// Leaves are `H(0x00 || item.encode())` and internal nodes are
+/// `H(0x01 || left || right)`. A level with an odd number of nodes pairs its
+/// last node with the default (all-zero) hash, so every leaf has a path of
+/// the same length and no item can be duplicated to forge the same root.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MerkleTree<H: Hash> {
+    /// Tree levels, from the leaves (`levels[0]`) up to the root level
+    levels: Vec<Vec<H::Output>>,
+}
+
+impl<H: Hash> MerkleTree<H> {
+    /// Build a Merkle tree from a list of items
+    pub fn new<T: Encode>(items: &[T]) -> Self {
+        let leaves = items.iter().map(|item| hash_leaf::<H>(&item.encode())).collect();
+        Self::from_leaves(leaves)
+    }
+
+    /// Build a Merkle tree from already hashed leaves
+    pub fn from_leaves(leaves: Vec<H::Output>) -> Self {
+        let mut levels = vec![leaves];
+
+        while levels[levels.len() - 1].len() > 1 {
+            let next = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| {
+                    let right = pair.get(1).copied().unwrap_or_default();
+                    hash_node::<H>(&pair[0], &right)
+                })
+                .collect();
+            levels.push(next);
+        }
+
+        Self { levels }
+    }
+
+    /// Merkle root, or the default hash for an empty tree
+    pub fn root(&self) -> H::Output {
+        self.levels
+            .last()
+            .and_then(|level| level.first())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// All tree levels, from the leaves up to the root
+    pub fn levels(&self) -> &[Vec<H::Output>] {
+        &self.levels
+    }
+
+    /// Number of leaves in the tree
+    pub fn leaf_count(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Number of hashing levels above the leaves
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    /// Sibling path from the leaf at `index` up to the root
+    pub fn proof(&self, index: usize) -> Option<Vec<H::Output>> {
+        if index >= self.leaf_count() {
+            return None;
+        }
+
+        let path = self.levels[..self.depth()]
+            .iter()
+            .enumerate()
+            .map(|(height, level)| level.get((index >> height) ^ 1).copied().unwrap_or_default())
+            .collect();
+
+        Some(path)
+    }
+
+    /// Deduplicated sibling hashes proving the leaves at `indices`
+    ///
+    /// `indices` must be strictly increasing. Siblings computable from the
+    /// proven leaves and padding siblings past the end of a level are
+    /// omitted; the rest are listed bottom-up, left to right.
+    pub fn multiproof(&self, indices: &[usize]) -> Option<Vec<H::Output>> {
+        if indices.is_empty()
+            || indices.windows(2).any(|w| w[0] >= w[1])
+            || indices[indices.len() - 1] >= self.leaf_count()
+        {
+            return None;
+        }
+
+        let mut known = indices.to_vec();
+        let mut hashes = Vec::new();
+        for level in &self.levels[..self.depth()] {
+            let mut i = 0;
+            while i < known.len() {
+                let index = known[i];
+                if index & 1 == 0 && known.get(i + 1) == Some(&(index + 1)) {
+                    i += 2;
+                    continue;
+                }
+                if let Some(sibling) = level.get(index ^ 1) {
+                    hashes.push(*sibling);
+                }
+                i += 1;
+            }
+            known = known.into_iter().map(|index| index >> 1).collect();
+            known.dedup();
+        }
+
+        Some(hashes)
+    }
+}
+
+/// Hash a SCALE-encoded leaf with the leaf domain prefix
+pub fn hash_leaf<H: Hash>(data: &[u8]) -> H::Output {
+    let mut buf = Vec::with_capacity(1 + data.len());
+    buf.push(MERKLE_LEAF_PREFIX);
+    buf.extend_from_slice(data);
+    <H as Hash>::hash(&buf)
+}
+
+/// Hash two child nodes with the node domain prefix
+pub fn hash_node<H: Hash>(left: &H::Output, right: &H::Output) -> H::Output {
+    let (left, right) = (left.as_ref(), right.as_ref());
+    let mut buf = Vec::with_capacity(1 + left.len() + right.len());
+    buf.push(MERKLE_NODE_PREFIX);
+    buf.extend_from_slice(left);
+    buf.extend_from_slice(right);
+    <H as Hash>::hash(&buf)
+}
+
 /// Build a JAM Merkle tree from a list of items
-pub fn build_merkle_tree<T: Encode>(items: &[T]) -> Vec<BlakeTwo256> {
-    // Implementation omitted for brevity
-    // In a complete implementation, this would construct a Merkle tree
-    // using the BlakeTwo256 hashing algorithm
-    Vec::new()
+pub fn build_merkle_tree<T: Encode>(items: &[T]) -> MerkleTree<BlakeTwo256> {
+    MerkleTree::new(items)
 }
 
 /// Generate a JAM proof for an item
+///
+/// The item is located in `items` by its SCALE encoding; the first match is
+/// proven. Returns `None` if the item is not part of the list.
 pub fn generate_jam_proof<T: Encode, H: Hash>(
     item: &T,
     items: &[T],
-    block_hash: H,
-) -> Option<JamProof<H, <H as Hash>::Output>> {
-    // Implementation omitted for brevity
-    // In a complete implementation, this would generate a Merkle proof
-    // for the given item within the list of items
-    None
+    block_hash: <H as Hash>::Output,
+) -> Option<JamProof<<H as Hash>::Output, <H as Hash>::Output>> {
+    let justified_data = item.encode();
+    let leaf = hash_leaf::<H>(&justified_data);
+    let tree = MerkleTree::<H>::new(items);
+    let leaf_index = tree.levels()[0].iter().position(|l| *l == leaf)?;
+
+    Some(JamProof {
+        block_hash,
+        merkle_root: tree.root(),
+        proof_path: tree.proof(leaf_index)?,
+        leaf_index: leaf_index as u64,
+        justified_data,
+    })
 }
 
 /// Verify a JAM proof
+///
+/// The proof is valid if it commits to `root` and hashing its justified data
+/// up along `proof_path`, in the order given by `leaf_index`, reproduces it.
 pub fn verify_jam_proof<H: Hash>(
-    proof: &JamProof<H, <H as Hash>::Output>,
+    proof: &JamProof<<H as Hash>::Output, <H as Hash>::Output>,
+    root: <H as Hash>::Output,
+) -> bool {
+    if proof.merkle_root != root {
+        return false;
+    }
+
+    let leaf = hash_leaf::<H>(&proof.justified_data);
+    compute_merkle_root::<H>(leaf, proof.leaf_index, &proof.proof_path) == Some(root)
+}
+
+/// Verify that `proofs` justify the data of an operation
+///
+/// Every proof must justify exactly `data` and verify against the root
+/// `trusted_root` returns for its block, so a proof committed for one
+/// operation cannot justify another. An empty list of proofs justifies
+/// nothing.
+pub fn verify_justification<H: Hash>(
+    data: &[u8],
+    proofs: &[JamProof<<H as Hash>::Output, <H as Hash>::Output>],
+    trusted_root: impl Fn(&<H as Hash>::Output) -> Option<<H as Hash>::Output>,
+) -> bool {
+    !proofs.is_empty()
+        && proofs.iter().all(|proof| {
+            proof.justified_data == data
+                && trusted_root(&proof.block_hash) == Some(proof.merkle_root)
+                && verify_jam_proof::<H>(proof, proof.merkle_root)
+        })
+}
+
+/// Recompute a Merkle root from a leaf hash and its sibling path
+///
+/// Returns `None` if `leaf_index` has bits set above the path length, as
+/// such an index cannot address a leaf of the tree.
+pub fn compute_merkle_root<H: Hash>(
+    leaf: <H as Hash>::Output,
+    leaf_index: u64,
+    proof_path: &[<H as Hash>::Output],
+) -> Option<<H as Hash>::Output> {
+    if leaf_index.checked_shr(proof_path.len() as u32).unwrap_or(0) != 0 {
+        return None;
+    }
+
+    let mut node = leaf;
+    let mut index = leaf_index;
+    for sibling in proof_path {
+        node = if index & 1 == 0 {
+            hash_node::<H>(&node, sibling)
+        } else {
+            hash_node::<H>(sibling, &node)
+        };
+        index >>= 1;
+    }
+
+    Some(node)
+}
+
+/// Generate a JAM multiproof for the items at `indices`
+///
+/// Indices may be given in any order and are deduplicated. Returns `None` if
+/// no index is given or any index is out of range.
+pub fn generate_jam_multiproof<T: Encode, H: Hash>(
+    indices: &[u64],
+    items: &[T],
+    block_hash: <H as Hash>::Output,
+) -> Option<JamMultiProof<<H as Hash>::Output, <H as Hash>::Output>> {
+    let mut leaf_indices = indices.to_vec();
+    leaf_indices.sort_unstable();
+    leaf_indices.dedup();
+
+    let positions = leaf_indices
+        .iter()
+        .map(|index| usize::try_from(*index).ok())
+        .collect::<Option<Vec<usize>>>()?;
+    let tree = MerkleTree::<H>::new(items);
+    let proof_hashes = tree.multiproof(&positions)?;
+
+    Some(JamMultiProof {
+        block_hash,
+        merkle_root: tree.root(),
+        leaf_count: items.len() as u64,
+        justified_data: positions.iter().map(|p| items[*p].encode()).collect(),
+        leaf_indices,
+        proof_hashes,
+    })
+}
+
+/// Verify a JAM multiproof
+///
+/// Every proven leaf is folded up the tree together, consuming
+/// `proof_hashes` in order. The proof is valid only if all hashes are
+/// consumed and the result equals `root`.
+pub fn verify_jam_multiproof<H: Hash>(
+    proof: &JamMultiProof<<H as Hash>::Output, <H as Hash>::Output>,
     root: <H as Hash>::Output,
 ) -> bool {
-    // Implementation omitted for brevity
-    // In a complete implementation, this would verify the Merkle proof
-    // against the provided Merkle root
-    false
+    let indices = &proof.leaf_indices;
+    if proof.merkle_root != root
+        || indices.is_empty()
+        || indices.len() != proof.justified_data.len()
+        || indices.windows(2).any(|w| w[0] >= w[1])
+        || indices[indices.len() - 1] >= proof.leaf_count
+    {
+        return false;
+    }
+
+    let mut nodes: Vec<(u64, <H as Hash>::Output)> = indices
+        .iter()
+        .zip(&proof.justified_data)
+        .map(|(index, data)| (*index, hash_leaf::<H>(data)))
+        .collect();
+    let mut proof_hashes = proof.proof_hashes.iter();
+    let mut width = proof.leaf_count;
+
+    while width > 1 {
+        let mut next = Vec::with_capacity(nodes.len());
+        let mut i = 0;
+        while i < nodes.len() {
+            let (index, node) = nodes[i];
+            let sibling = match nodes.get(i + 1) {
+                Some((next_index, next_node)) if index & 1 == 0 && *next_index == index + 1 => {
+                    i += 1;
+                    *next_node
+                }
+                _ if index ^ 1 >= width => Default::default(),
+                _ => match proof_hashes.next() {
+                    Some(hash) => *hash,
+                    None => return false,
+                },
+            };
+            let parent = if index & 1 == 0 {
+                hash_node::<H>(&node, &sibling)
+            } else {
+                hash_node::<H>(&sibling, &node)
+            };
+            next.push((index >> 1, parent));
+            i += 1;
+        }
+        nodes = next;
+        width = width.div_ceil(2);
+    }
+
+    proof_hashes.next().is_none() && nodes.len() == 1 && nodes[0].1 == root
 }
 
+/// JAM operation as tracked by a coordinator hashing with `H`
+pub type CoordinatedOperation<AccountId, BlockNumber, H> =
+    JamOperation<AccountId, BlockNumber, <H as Hash>::Output>;
+
 /// JAM Coordinator for managing cross-chain operations
-pub struct JamCoordinator<AccountId, BlockNumber, Hash> {
-    /// Pending operations
-    pending_operations: Vec<JamOperation<AccountId, BlockNumber, Hash>>,
+///
+/// Operations are keyed by the hash of their submitted encoding and move
+/// through `Pending -> InProgress -> Completed`, or to `Failed`/`Expired`,
+/// as the caller drives the coordinator with the current block number.
+/// Each operation type is validated and executed by its registered executor.
+/// Completed operations are appended to an MMR so their completion can be
+/// proven after they have been pruned.
+pub struct JamCoordinator<AccountId, BlockNumber, H: Hash = BlakeTwo256> {
+    /// Operations by hash
+    operations: BTreeMap<H::Output, CoordinatedOperation<AccountId, BlockNumber, H>>,
+    /// Maximum number of pending and in-progress operations
+    max_active_operations: u32,
+    /// Trusted Merkle roots by block hash, used to verify operation proofs
+    trusted_roots: BTreeMap<H::Output, H::Output>,
+    /// History of completed operations
+    history: MerkleMountainRange<H>,
+    /// MMR leaf index of each completed operation
+    history_index: BTreeMap<H::Output, u64>,
+    /// Executors by operation type
+    executors: BTreeMap<JamOperationType, BoxedExecutor<AccountId, BlockNumber, H>>,
+    /// Multi-parachain group operations by hash
+    groups: BTreeMap<H::Output, JamGroupOperation<AccountId, BlockNumber>>,
+}
+
+impl<AccountId, BlockNumber, H: Hash> Default for JamCoordinator<AccountId, BlockNumber, H>
+where
+    AccountId: Encode + PartialEq,
+    BlockNumber: Encode + Ord + Copy,
+    H::Output: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl<AccountId, BlockNumber, Hash> JamCoordinator<AccountId, BlockNumber, Hash> {
+impl<AccountId, BlockNumber, H: Hash> JamCoordinator<AccountId, BlockNumber, H>
+where
+    AccountId: Encode + PartialEq,
+    BlockNumber: Encode + Ord + Copy,
+    H::Output: Ord,
+{
     /// Create a new JAM Coordinator
     pub fn new() -> Self {
+        Self::with_max_active_operations(DEFAULT_MAX_ACTIVE_OPERATIONS)
+    }
+
+    /// Create a new JAM Coordinator with a custom active operation limit
+    pub fn with_max_active_operations(max_active_operations: u32) -> Self {
         Self {
-            pending_operations: Vec::new(),
+            operations: BTreeMap::new(),
+            max_active_operations,
+            trusted_roots: BTreeMap::new(),
+            history: MerkleMountainRange::new(),
+            history_index: BTreeMap::new(),
+            executors: BTreeMap::new(),
+            groups: BTreeMap::new(),
         }
     }
 
+    /// Register the executor handling `operation_type`
+    pub fn register_executor(
+        &mut self,
+        operation_type: JamOperationType,
+        executor: BoxedExecutor<AccountId, BlockNumber, H>,
+    ) -> Result<(), JamError> {
+        if self.executors.contains_key(&operation_type) {
+            return Err(JamError::ExecutorAlreadyRegistered);
+        }
+
+        self.executors.insert(operation_type, executor);
+        Ok(())
+    }
+
+    /// Remove and return the executor handling `operation_type`
+    ///
+    /// Operations of that type already accepted fail when executed.
+    pub fn unregister_executor(
+        &mut self,
+        operation_type: JamOperationType,
+    ) -> Option<BoxedExecutor<AccountId, BlockNumber, H>> {
+        self.executors.remove(&operation_type)
+    }
+
+    /// Trust `merkle_root` as the JAM root committed in `block_hash`
+    pub fn import_merkle_root(&mut self, block_hash: H::Output, merkle_root: H::Output) {
+        self.trusted_roots.insert(block_hash, merkle_root);
+    }
+
+    /// Stop trusting the JAM root committed in `block_hash`
+    pub fn remove_merkle_root(&mut self, block_hash: &H::Output) {
+        self.trusted_roots.remove(block_hash);
+    }
+
     /// Submit a new JAM operation
-    pub fn submit_operation(&mut self, operation: JamOperation<AccountId, BlockNumber, Hash>) {
-        self.pending_operations.push(operation);
+    ///
+    /// The operation must pass its executor's validation. Returns the
+    /// operation hash under which it can be queried.
+    pub fn submit_operation(
+        &mut self,
+        operation: CoordinatedOperation<AccountId, BlockNumber, H>,
+        now: BlockNumber,
+    ) -> Result<H::Output, JamError> {
+        if operation.status != JamOperationStatus::Pending {
+            return Err(JamError::NotPending);
+        }
+        if operation.expires_at <= now {
+            return Err(JamError::OperationExpired);
+        }
+
+        let hash = <H as Hash>::hash_of(&operation);
+        if self.operations.contains_key(&hash) {
+            return Err(JamError::OperationAlreadyExists);
+        }
+        if self.active_count() >= self.max_active_operations as usize {
+            return Err(JamError::TooManyOperations);
+        }
+        self.executors
+            .get(&operation.operation_type)
+            .ok_or(JamError::UnsupportedOperationType)?
+            .validate(&operation)?;
+
+        self.operations.insert(hash, operation);
+        Ok(hash)
     }
 
     /// Execute pending JAM operations
-    pub fn execute_operations(&mut self) {
-        // Implementation omitted for brevity
+    ///
+    /// Expires every active operation whose `expires_at` has been reached,
+    /// then verifies the proofs of each pending operation and hands it to
+    /// its executor. Operations without proofs or with invalid proofs, or
+    /// whose executor returns an error, fail. Returns every status change in the order it
+    /// was applied.
+    pub fn execute_operations(&mut self, now: BlockNumber) -> Vec<(H::Output, JamOperationStatus)> {
+        let mut changes = self.expire_operations(now);
+
+        let pending = self.operations_by_status(JamOperationStatus::Pending);
+        for hash in pending {
+            if self.verify_operation_proofs(&hash).is_err() {
+                if self.set_status(&hash, JamOperationStatus::Failed).is_ok() {
+                    changes.push((hash, JamOperationStatus::Failed));
+                }
+                continue;
+            }
+            if self.set_status(&hash, JamOperationStatus::InProgress).is_err() {
+                continue;
+            }
+            changes.push((hash, JamOperationStatus::InProgress));
+
+            let status = match self.dispatch(&hash) {
+                Ok(JamExecution::Completed) => JamOperationStatus::Completed,
+                Ok(JamExecution::Dispatched) => continue,
+                Err(_) => JamOperationStatus::Failed,
+            };
+            if self.set_status(&hash, status).is_ok() {
+                if status == JamOperationStatus::Completed {
+                    self.record_completion(&hash);
+                }
+                changes.push((hash, status));
+            }
+        }
+
+        changes
+    }
+
+    /// Expire every active operation whose `expires_at` has been reached
+    pub fn expire_operations(&mut self, now: BlockNumber) -> Vec<(H::Output, JamOperationStatus)> {
+        let overdue: Vec<H::Output> = self
+            .operations
+            .iter()
+            .filter(|(_, op)| !op.status.is_final() && op.expires_at <= now)
+            .map(|(hash, _)| *hash)
+            .collect();
+
+        overdue
+            .into_iter()
+            .filter(|hash| self.set_status(hash, JamOperationStatus::Expired).is_ok())
+            .map(|hash| (hash, JamOperationStatus::Expired))
+            .collect()
+    }
+
+    /// Mark an in-progress operation as completed
+    ///
+    /// An operation reported after its expiry is expired instead.
+    pub fn complete_operation(&mut self, hash: &H::Output, now: BlockNumber) -> Result<(), JamError> {
+        let operation = self.operations.get(hash).ok_or(JamError::OperationNotFound)?;
+        if !operation.status.is_final() && operation.expires_at <= now {
+            self.set_status(hash, JamOperationStatus::Expired)?;
+            return Err(JamError::OperationExpired);
+        }
+
+        self.set_status(hash, JamOperationStatus::Completed)?;
+        self.record_completion(hash);
+        Ok(())
+    }
+
+    /// Mark a pending or in-progress operation as failed
+    pub fn fail_operation(&mut self, hash: &H::Output) -> Result<(), JamError> {
+        self.set_status(hash, JamOperationStatus::Failed)
+    }
+
+    /// Operation by hash
+    pub fn operation(&self, hash: &H::Output) -> Option<&CoordinatedOperation<AccountId, BlockNumber, H>> {
+        self.operations.get(hash)
+    }
+
+    /// Hashes of the operations with the given status, in ascending order
+    pub fn operations_by_status(&self, status: JamOperationStatus) -> Vec<H::Output> {
+        self.operations
+            .iter()
+            .filter(|(_, op)| op.status == status)
+            .map(|(hash, _)| *hash)
+            .collect()
+    }
+
+    /// Hashes of the operations submitted by `initiator`, in ascending order
+    pub fn operations_by_initiator(&self, initiator: &AccountId) -> Vec<H::Output> {
+        self.operations
+            .iter()
+            .filter(|(_, op)| op.initiator == *initiator)
+            .map(|(hash, _)| *hash)
+            .collect()
+    }
+
+    /// Remove all operations with a final status and return them
+    ///
+    /// Completed operations remain provable through the history MMR.
+    pub fn prune_finalized(&mut self) -> Vec<(H::Output, CoordinatedOperation<AccountId, BlockNumber, H>)> {
+        let finalized: Vec<H::Output> = self
+            .operations
+            .iter()
+            .filter(|(_, op)| op.status.is_final())
+            .map(|(hash, _)| *hash)
+            .collect();
+
+        finalized
+            .into_iter()
+            .filter_map(|hash| self.operations.remove(&hash).map(|op| (hash, op)))
+            .collect()
+    }
+
+    /// History of completed operations
+    pub fn history(&self) -> &MerkleMountainRange<H> {
+        &self.history
+    }
+
+    /// Prove that a completed operation is part of the current history root
+    pub fn history_proof(&self, hash: &H::Output) -> Option<MmrProof<H::Output>> {
+        let leaf_index = *self.history_index.get(hash)?;
+        self.history.inclusion_proof(leaf_index, self.history.leaf_count())
+    }
+
+    /// Number of pending and in-progress operations and undecided groups
+    fn active_count(&self) -> usize {
+        let operations = self.operations.values().filter(|op| !op.status.is_final()).count();
+        let groups = self.groups.values().filter(|group| group.status == JamGroupStatus::Preparing).count();
+        operations + groups
+    }
+
+    /// Verify that the proofs of an operation justify its data against the
+    /// trusted roots
+    fn verify_operation_proofs(&self, hash: &H::Output) -> Result<(), JamError> {
+        let operation = self.operations.get(hash).ok_or(JamError::OperationNotFound)?;
+        let valid = verify_justification::<H>(&operation.data, &operation.proofs, |block_hash| {
+            self.trusted_roots.get(block_hash).copied()
+        });
+
+        if valid {
+            Ok(())
+        } else {
+            Err(JamError::InvalidProof)
+        }
+    }
+
+    /// Hand an in-progress operation to the executor for its type
+    fn dispatch(&mut self, hash: &H::Output) -> Result<JamExecution, JamError> {
+        let operation = self.operations.get(hash).ok_or(JamError::OperationNotFound)?;
+        self.executors
+            .get_mut(&operation.operation_type)
+            .ok_or(JamError::UnsupportedOperationType)?
+            .execute(operation)
+    }
+
+    /// Append a completed operation to the history MMR
+    fn record_completion(&mut self, hash: &H::Output) {
+        if let Some(operation) = self.operations.get(hash) {
+            let leaf_index = self.history.append(operation);
+            self.history_index.insert(*hash, leaf_index);
+        }
+    }
+
+    /// Move an operation to `to` if its lifecycle allows it
+    fn set_status(&mut self, hash: &H::Output, to: JamOperationStatus) -> Result<(), JamError> {
+        let operation = self.operations.get_mut(hash).ok_or(JamError::OperationNotFound)?;
+        if !operation.status.can_transition_to(to) {
+            return Err(JamError::InvalidTransition { from: operation.status, to });
+        }
+
+        operation.status = to;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use executor::JamExecutor;
+    use sp_runtime::testing::H256;
+    use sp_runtime::traits::Keccak256;
+
+    #[test]
+    fn test_empty_tree() {
+        let tree = build_merkle_tree::<u32>(&[]);
+        assert_eq!(tree.leaf_count(), 0);
+        assert_eq!(tree.depth(), 0);
+        assert_eq!(tree.root(), Default::default());
+    }
+
+    #[test]
+    fn test_single_leaf_root() {
+        let tree = build_merkle_tree(&[7u32]);
+        assert_eq!(tree.depth(), 0);
+        assert_eq!(tree.root(), hash_leaf::<BlakeTwo256>(&7u32.encode()));
+    }
+
+    #[test]
+    fn test_tree_levels() {
+        let items = [1u32, 2, 3, 4, 5];
+        let tree = build_merkle_tree(&items);
+        let widths: Vec<usize> = tree.levels().iter().map(|level| level.len()).collect();
+        assert_eq!(widths, vec![5, 3, 2, 1]);
+
+        let leaves: Vec<_> = items.iter().map(|i| hash_leaf::<BlakeTwo256>(&i.encode())).collect();
+        let n01 = hash_node::<BlakeTwo256>(&leaves[0], &leaves[1]);
+        let n23 = hash_node::<BlakeTwo256>(&leaves[2], &leaves[3]);
+        let n4 = hash_node::<BlakeTwo256>(&leaves[4], &Default::default());
+        let n0123 = hash_node::<BlakeTwo256>(&n01, &n23);
+        let n4_ = hash_node::<BlakeTwo256>(&n4, &Default::default());
+        assert_eq!(tree.root(), hash_node::<BlakeTwo256>(&n0123, &n4_));
+    }
+
+    #[test]
+    fn test_odd_leaf_not_duplicated() {
+        let odd = build_merkle_tree(&[1u32, 2, 3]);
+        let padded = build_merkle_tree(&[1u32, 2, 3, 3]);
+        assert_ne!(odd.root(), padded.root());
+    }
+
+    #[test]
+    fn test_leaf_and_node_domains_differ() {
+        let a = hash_leaf::<BlakeTwo256>(&1u32.encode());
+        let b = hash_leaf::<BlakeTwo256>(&2u32.encode());
+        let node = hash_node::<BlakeTwo256>(&a, &b);
+
+        let mut forged = a.as_ref().to_vec();
+        forged.extend_from_slice(b.as_ref());
+        assert_ne!(hash_leaf::<BlakeTwo256>(&forged), node);
+    }
+
+    fn all_proofs(items: &[u32]) -> Vec<JamProof<H256, H256>> {
+        items
+            .iter()
+            .map(|item| generate_jam_proof::<_, BlakeTwo256>(item, items, H256::repeat_byte(0xbb)).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_generate_and_verify_proofs() {
+        for size in 1..=17u32 {
+            let items: Vec<u32> = (0..size).collect();
+            let root = build_merkle_tree(&items).root();
+
+            for (index, proof) in all_proofs(&items).iter().enumerate() {
+                assert_eq!(proof.leaf_index, index as u64);
+                assert_eq!(proof.merkle_root, root);
+                assert_eq!(proof.justified_data, items[index].encode());
+                assert!(verify_jam_proof::<BlakeTwo256>(proof, root));
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_proof_missing_item() {
+        let items = [1u32, 2, 3];
+        assert!(generate_jam_proof::<_, BlakeTwo256>(&4u32, &items, H256::zero()).is_none());
+        assert!(generate_jam_proof::<_, BlakeTwo256>(&1u32, &[], H256::zero()).is_none());
+    }
+
+    #[test]
+    fn test_verify_rejects_wrong_root() {
+        let items = [1u32, 2, 3];
+        let proof = &all_proofs(&items)[1];
+        let other_root = build_merkle_tree(&[1u32, 2, 4]).root();
+        assert!(!verify_jam_proof::<BlakeTwo256>(proof, other_root));
+
+        let mut forged = proof.clone();
+        forged.merkle_root = other_root;
+        assert!(!verify_jam_proof::<BlakeTwo256>(&forged, proof.merkle_root));
+    }
+
+    #[test]
+    fn test_flipped_path_byte_rejected() {
+        let items: Vec<u32> = (0..11).collect();
+        for proof in all_proofs(&items) {
+            for node in 0..proof.proof_path.len() {
+                for byte in 0..32 {
+                    let mut tampered = proof.clone();
+                    tampered.proof_path[node].as_mut()[byte] ^= 0x01;
+                    assert!(!verify_jam_proof::<BlakeTwo256>(&tampered, proof.merkle_root));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_flipped_leaf_byte_rejected() {
+        let items: Vec<u32> = (0..11).collect();
+        for proof in all_proofs(&items) {
+            for byte in 0..proof.justified_data.len() {
+                for bit in 0..8 {
+                    let mut tampered = proof.clone();
+                    tampered.justified_data[byte] ^= 1 << bit;
+                    assert!(!verify_jam_proof::<BlakeTwo256>(&tampered, proof.merkle_root));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_flipped_index_rejected() {
+        let items: Vec<u32> = (0..11).collect();
+        for proof in all_proofs(&items) {
+            for bit in 0..64 {
+                let mut tampered = proof.clone();
+                tampered.leaf_index ^= 1 << bit;
+                assert!(!verify_jam_proof::<BlakeTwo256>(&tampered, proof.merkle_root));
+            }
+        }
+    }
+
+    #[test]
+    fn test_proofs_generic_over_hasher() {
+        let items = [10u64, 20, 30, 40, 50];
+        let root = MerkleTree::<Keccak256>::new(&items).root();
+        let proof = generate_jam_proof::<_, Keccak256>(&30u64, &items, H256::zero()).unwrap();
+        assert!(verify_jam_proof::<Keccak256>(&proof, root));
+        assert!(!verify_jam_proof::<BlakeTwo256>(&proof, root));
+    }
+
+    fn multiproof(indices: &[u64], items: &[u32]) -> JamMultiProof<H256, H256> {
+        generate_jam_multiproof::<_, BlakeTwo256>(indices, items, H256::repeat_byte(0xbb)).unwrap()
+    }
+
+    #[test]
+    fn test_multiproof_all_subsets() {
+        for size in 1..=9u32 {
+            let items: Vec<u32> = (0..size).collect();
+            let root = build_merkle_tree(&items).root();
+
+            for mask in 1u32..(1 << size) {
+                let indices: Vec<u64> = (0..size as u64).filter(|i| mask & (1 << i) != 0).collect();
+                let proof = multiproof(&indices, &items);
+                assert_eq!(proof.leaf_indices, indices);
+                assert!(verify_jam_multiproof::<BlakeTwo256>(&proof, root));
+            }
+        }
+    }
+
+    #[test]
+    fn test_multiproof_invalid_indices() {
+        let items = [1u32, 2, 3];
+        assert!(generate_jam_multiproof::<_, BlakeTwo256>(&[], &items, H256::zero()).is_none());
+        assert!(generate_jam_multiproof::<_, BlakeTwo256>(&[0, 3], &items, H256::zero()).is_none());
+
+        let proof = multiproof(&[2, 0, 2], &items);
+        assert_eq!(proof.leaf_indices, vec![0, 2]);
+    }
+
+    #[test]
+    fn test_multiproof_tampering_rejected() {
+        let items: Vec<u32> = (0..13).collect();
+        let root = build_merkle_tree(&items).root();
+        let proof = multiproof(&[1, 2, 7, 12], &items);
+        assert!(verify_jam_multiproof::<BlakeTwo256>(&proof, root));
+
+        for node in 0..proof.proof_hashes.len() {
+            let mut tampered = proof.clone();
+            tampered.proof_hashes[node].as_mut()[0] ^= 0x01;
+            assert!(!verify_jam_multiproof::<BlakeTwo256>(&tampered, root));
+        }
+
+        let mut tampered = proof.clone();
+        tampered.justified_data[2][0] ^= 0x01;
+        assert!(!verify_jam_multiproof::<BlakeTwo256>(&tampered, root));
+
+        let mut tampered = proof.clone();
+        tampered.leaf_indices[1] = 3;
+        assert!(!verify_jam_multiproof::<BlakeTwo256>(&tampered, root));
+
+        let mut tampered = proof.clone();
+        tampered.leaf_count = 16;
+        assert!(!verify_jam_multiproof::<BlakeTwo256>(&tampered, root));
+
+        let mut tampered = proof.clone();
+        tampered.proof_hashes.push(H256::zero());
+        assert!(!verify_jam_multiproof::<BlakeTwo256>(&tampered, root));
+
+        let mut tampered = proof;
+        tampered.proof_hashes.pop();
+        assert!(!verify_jam_multiproof::<BlakeTwo256>(&tampered, root));
+    }
+
+    #[test]
+    fn test_multiproof_smaller_than_individual_proofs() {
+        let items: Vec<u32> = (0..64).collect();
+        let indices: Vec<u64> = (16..32).collect();
+
+        let individual: usize = all_proofs(&items)[16..32].iter().map(|p| p.encoded_size()).sum();
+        let batched = multiproof(&indices, &items);
+        assert!(batched.encoded_size() * 4 < individual);
+
+        // 16 aligned leaves of a 64-leaf tree need only the two siblings above
+        // their subtree instead of 16 paths of six hashes each.
+        assert_eq!(batched.proof_hashes.len(), 2);
+
+        let scattered: Vec<u64> = (0..64).step_by(8).collect();
+        let individual: usize = scattered.iter().map(|i| all_proofs(&items)[*i as usize].encoded_size()).sum();
+        assert!(multiproof(&scattered, &items).encoded_size() < individual);
+    }
+
+    type TestCoordinator = JamCoordinator<u64, u32>;
+
+    /// Executor returning a fixed result and counting executions
+    struct FixedExecutor {
+        result: Result<JamExecution, JamError>,
+        executed: sp_std::rc::Rc<core::cell::Cell<u32>>,
+    }
+
+    impl FixedExecutor {
+        fn boxed(result: Result<JamExecution, JamError>) -> BoxedExecutor<u64, u32, BlakeTwo256> {
+            Box::new(Self { result, executed: Default::default() })
+        }
+    }
+
+    impl JamExecutor<u64, u32, BlakeTwo256> for FixedExecutor {
+        fn validate(&self, operation: &JamOperation<u64, u32, H256>) -> Result<(), JamError> {
+            if operation.data.is_empty() {
+                return Err(JamError::Custom(1));
+            }
+            Ok(())
+        }
+
+        fn execute(&mut self, _operation: &JamOperation<u64, u32, H256>) -> Result<JamExecution, JamError> {
+            self.executed.set(self.executed.get() + 1);
+            self.result
+        }
+    }
+
+    const TRUSTED_BLOCK: H256 = H256::repeat_byte(0xaa);
+
+    /// Items committed in `TRUSTED_BLOCK`, one per test operation data byte
+    fn trusted_items() -> Vec<u8> {
+        (0..=u8::MAX).collect()
+    }
+
+    /// Proof justifying the data of a test operation
+    fn justification(data: u8) -> JamProof<H256, H256> {
+        generate_jam_proof::<_, BlakeTwo256>(&data, &trusted_items(), TRUSTED_BLOCK).unwrap()
+    }
+
+    fn coordinator_with(max_active_operations: u32) -> TestCoordinator {
+        let mut coordinator = TestCoordinator::with_max_active_operations(max_active_operations);
+        coordinator
+            .register_executor(JamOperationType::MessagePassing, FixedExecutor::boxed(Ok(JamExecution::Dispatched)))
+            .unwrap();
+        coordinator.import_merkle_root(TRUSTED_BLOCK, build_merkle_tree(&trusted_items()).root());
+        coordinator
+    }
+
+    fn operation(initiator: u64, data: u8, expires_at: u32) -> JamOperation<u64, u32, H256> {
+        JamOperation {
+            initiator,
+            target_parachain_id: 2000,
+            operation_type: JamOperationType::MessagePassing,
+            data: vec![data],
+            created_at: 1,
+            expires_at,
+            proofs: vec![justification(data)],
+            status: JamOperationStatus::Pending,
+        }
+    }
+
+    fn status_of(coordinator: &TestCoordinator, hash: &H256) -> JamOperationStatus {
+        coordinator.operation(hash).unwrap().status
+    }
+
+    #[test]
+    fn test_operation_lifecycle() {
+        let mut coordinator = coordinator_with(DEFAULT_MAX_ACTIVE_OPERATIONS);
+        let hash = coordinator.submit_operation(operation(1, 0, 10), 1).unwrap();
+        assert_eq!(status_of(&coordinator, &hash), JamOperationStatus::Pending);
+
+        let changes = coordinator.execute_operations(2);
+        assert_eq!(changes, vec![(hash, JamOperationStatus::InProgress)]);
+
+        coordinator.complete_operation(&hash, 3).unwrap();
+        assert_eq!(status_of(&coordinator, &hash), JamOperationStatus::Completed);
+        assert!(coordinator.execute_operations(4).is_empty());
+    }
+
+    #[test]
+    fn test_submit_rejections() {
+        let mut coordinator = coordinator_with(2);
+        assert_eq!(coordinator.submit_operation(operation(1, 0, 5), 5), Err(JamError::OperationExpired));
+
+        let mut started = operation(1, 0, 10);
+        started.status = JamOperationStatus::InProgress;
+        assert_eq!(coordinator.submit_operation(started, 1), Err(JamError::NotPending));
+
+        coordinator.submit_operation(operation(1, 0, 10), 1).unwrap();
+        assert_eq!(
+            coordinator.submit_operation(operation(1, 0, 10), 1),
+            Err(JamError::OperationAlreadyExists)
+        );
+
+        let second = coordinator.submit_operation(operation(1, 1, 10), 1).unwrap();
+        assert_eq!(coordinator.submit_operation(operation(1, 2, 10), 1), Err(JamError::TooManyOperations));
+
+        coordinator.fail_operation(&second).unwrap();
+        assert!(coordinator.submit_operation(operation(1, 2, 10), 1).is_ok());
+    }
+
+    #[test]
+    fn test_operations_expire() {
+        let mut coordinator = coordinator_with(DEFAULT_MAX_ACTIVE_OPERATIONS);
+        let pending = coordinator.submit_operation(operation(1, 0, 5), 1).unwrap();
+        let started = coordinator.submit_operation(operation(1, 1, 8), 1).unwrap();
+        coordinator.execute_operations(2);
+        let late = coordinator.submit_operation(operation(1, 2, 8), 2).unwrap();
+
+        let mut changes = coordinator.execute_operations(5);
+        changes.sort_by_key(|(hash, _)| *hash);
+        let mut expected = vec![(pending, JamOperationStatus::Expired), (late, JamOperationStatus::InProgress)];
+        expected.sort_by_key(|(hash, _)| *hash);
+        assert_eq!(changes, expected);
+
+        assert_eq!(coordinator.complete_operation(&started, 8), Err(JamError::OperationExpired));
+        assert_eq!(status_of(&coordinator, &started), JamOperationStatus::Expired);
+        assert_eq!(coordinator.expire_operations(8), vec![(late, JamOperationStatus::Expired)]);
+    }
+
+    #[test]
+    fn test_proofs_verified_before_execution() {
+        let items = [1u32, 2, 3];
+        let block_hash = H256::repeat_byte(0xbb);
+        let root = build_merkle_tree(&items).root();
+        let proof = generate_jam_proof::<_, BlakeTwo256>(&2u32, &items, block_hash).unwrap();
+
+        let justified_by = |data: Vec<u8>, proof: JamProof<H256, H256>| {
+            let mut op = operation(1, 0, 10);
+            op.data = data;
+            op.proofs = vec![proof];
+            op
+        };
+
+        let mut coordinator = coordinator_with(DEFAULT_MAX_ACTIVE_OPERATIONS);
+        let justified = coordinator.submit_operation(justified_by(2u32.encode(), proof.clone()), 1).unwrap();
+
+        let mut bad_proof = proof.clone();
+        bad_proof.proof_path[0].as_mut()[0] ^= 0x01;
+        let tampered = coordinator.submit_operation(justified_by(2u32.encode(), bad_proof), 1).unwrap();
+
+        // A valid proof only justifies the data it commits to
+        let unrelated = coordinator.submit_operation(justified_by(3u32.encode(), proof.clone()), 1).unwrap();
+
+        // Without a trusted root nothing can be justified
+        let mut untrusted = coordinator_with(DEFAULT_MAX_ACTIVE_OPERATIONS);
+        let hash = untrusted.submit_operation(justified_by(2u32.encode(), proof), 1).unwrap();
+        untrusted.execute_operations(2);
+        assert_eq!(status_of(&untrusted, &hash), JamOperationStatus::Failed);
+
+        coordinator.import_merkle_root(block_hash, root);
+        coordinator.execute_operations(2);
+        assert_eq!(status_of(&coordinator, &justified), JamOperationStatus::InProgress);
+        assert_eq!(status_of(&coordinator, &tampered), JamOperationStatus::Failed);
+        assert_eq!(status_of(&coordinator, &unrelated), JamOperationStatus::Failed);
+    }
+
+    #[test]
+    fn test_operations_without_proofs_fail() {
+        let mut coordinator = coordinator_with(DEFAULT_MAX_ACTIVE_OPERATIONS);
+        let mut unjustified = operation(1, 0, 10);
+        unjustified.proofs.clear();
+        let unjustified = coordinator.submit_operation(unjustified, 1).unwrap();
+        let justified = coordinator.submit_operation(operation(1, 1, 10), 1).unwrap();
+
+        let changes = coordinator.execute_operations(2);
+        assert!(changes.contains(&(unjustified, JamOperationStatus::Failed)));
+        assert!(changes.contains(&(justified, JamOperationStatus::InProgress)));
+        assert_eq!(changes.len(), 2);
+    }
+
+    #[test]
+    fn test_illegal_transitions_rejected() {
+        let mut coordinator = coordinator_with(DEFAULT_MAX_ACTIVE_OPERATIONS);
+        let hash = coordinator.submit_operation(operation(1, 0, 10), 1).unwrap();
+
+        assert_eq!(
+            coordinator.complete_operation(&hash, 2),
+            Err(JamError::InvalidTransition {
+                from: JamOperationStatus::Pending,
+                to: JamOperationStatus::Completed,
+            })
+        );
+
+        coordinator.fail_operation(&hash).unwrap();
+        assert_eq!(
+            coordinator.fail_operation(&hash),
+            Err(JamError::InvalidTransition {
+                from: JamOperationStatus::Failed,
+                to: JamOperationStatus::Failed,
+            })
+        );
+        assert_eq!(coordinator.fail_operation(&H256::zero()), Err(JamError::OperationNotFound));
+    }
+
+    #[test]
+    fn test_queries_and_history() {
+        let mut coordinator = coordinator_with(DEFAULT_MAX_ACTIVE_OPERATIONS);
+        let a = coordinator.submit_operation(operation(1, 0, 10), 1).unwrap();
+        let b = coordinator.submit_operation(operation(2, 1, 10), 1).unwrap();
+        let c = coordinator.submit_operation(operation(1, 2, 10), 1).unwrap();
+        coordinator.execute_operations(2);
+        coordinator.complete_operation(&a, 3).unwrap();
+        coordinator.fail_operation(&c).unwrap();
+
+        let by_initiator = coordinator.operations_by_initiator(&1);
+        assert_eq!(by_initiator.len(), 2);
+        assert!(by_initiator.contains(&a) && by_initiator.contains(&c));
+        assert_eq!(coordinator.operations_by_status(JamOperationStatus::InProgress), vec![b]);
+
+        let completed = coordinator.operation(&a).unwrap().clone();
+        let proof = coordinator.history_proof(&a).unwrap();
+        assert!(coordinator.history_proof(&c).is_none());
+
+        let pruned = coordinator.prune_finalized();
+        assert_eq!(pruned.len(), 2);
+        assert!(coordinator.operation(&a).is_none());
+
+        let leaf = hash_leaf::<BlakeTwo256>(&completed.encode());
+        assert!(mmr::verify_mmr_proof::<BlakeTwo256>(&proof, leaf, coordinator.history().root()));
+    }
+
+    #[test]
+    fn test_executors_dispatch_by_type() {
+        let mut coordinator = coordinator_with(DEFAULT_MAX_ACTIVE_OPERATIONS);
+        let executed = sp_std::rc::Rc::new(core::cell::Cell::new(0));
+        let harvest = FixedExecutor { result: Ok(JamExecution::Completed), executed: executed.clone() };
+        coordinator.register_executor(JamOperationType::Custom(7), Box::new(harvest)).unwrap();
+        coordinator
+            .register_executor(JamOperationType::AssetTransfer, FixedExecutor::boxed(Err(JamError::Custom(2))))
+            .unwrap();
+
+        let mut anchored = operation(1, 0, 10);
+        anchored.operation_type = JamOperationType::Custom(7);
+        let anchored = coordinator.submit_operation(anchored, 1).unwrap();
+        let mut transfer = operation(1, 1, 10);
+        transfer.operation_type = JamOperationType::AssetTransfer;
+        let transfer = coordinator.submit_operation(transfer, 1).unwrap();
+        let message = coordinator.submit_operation(operation(1, 2, 10), 1).unwrap();
+
+        let changes = coordinator.execute_operations(2);
+        assert!(changes.contains(&(anchored, JamOperationStatus::Completed)));
+        assert!(changes.contains(&(transfer, JamOperationStatus::Failed)));
+        assert!(changes.contains(&(message, JamOperationStatus::InProgress)));
+        assert_eq!(changes.len(), 5);
+        assert_eq!(executed.get(), 1);
+        assert!(coordinator.history_proof(&anchored).is_some());
+    }
+
+    #[test]
+    fn test_executor_registration_and_validation() {
+        let mut coordinator = coordinator_with(DEFAULT_MAX_ACTIVE_OPERATIONS);
+        assert_eq!(
+            coordinator.register_executor(JamOperationType::MessagePassing, FixedExecutor::boxed(Ok(JamExecution::Completed))),
+            Err(JamError::ExecutorAlreadyRegistered)
+        );
+
+        let mut unsupported = operation(1, 0, 10);
+        unsupported.operation_type = JamOperationType::Custom(9);
+        assert_eq!(coordinator.submit_operation(unsupported, 1), Err(JamError::UnsupportedOperationType));
+
+        let mut empty = operation(1, 0, 10);
+        empty.data.clear();
+        assert_eq!(coordinator.submit_operation(empty, 1), Err(JamError::Custom(1)));
+
+        let hash = coordinator.submit_operation(operation(1, 0, 10), 1).unwrap();
+        assert!(coordinator.unregister_executor(JamOperationType::MessagePassing).is_some());
+        coordinator.execute_operations(2);
+        assert_eq!(status_of(&coordinator, &hash), JamOperationStatus::Failed);
     }
 }
@@ -0,0 +1,417 @@
+//! Merkle Mountain Range accumulator for append-only JAM history
+//!
+//! Completed JAM operations are appended as leaves. The accumulator is a list
+//! of perfect binary trees ("mountains") whose roots are the peaks; the MMR
+//! root commits to the peaks and the leaf count. Light clients only need to
+//! keep the latest root: inclusion proofs show that an operation is part of
+//! any historical root, and ancestry proofs show that an older root is a
+//! prefix of a newer one.
+
+use sp_std::prelude::*;
+use sp_runtime::traits::Hash;
+use scale_info::TypeInfo;
+use codec::{Encode, Decode};
+
+use crate::{hash_leaf, hash_node};
+
+/// Domain separation prefix for MMR roots
+pub const MMR_ROOT_PREFIX: u8 = 0x02;
+
+/// Proof that a leaf is included in an MMR of a given size
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo)]
+pub struct MmrProof<MerkleHash> {
+    /// Number of leaves in the MMR the proof is against
+    pub leaf_count: u64,
+    /// Leaf index
+    pub leaf_index: u64,
+    /// Sibling path from the leaf up to its peak
+    pub proof_path: Vec<MerkleHash>,
+    /// All other peaks, from left to right
+    pub peaks: Vec<MerkleHash>,
+}
+
+/// Proof that an MMR of `prev_leaf_count` leaves is a prefix of an MMR of
+/// `leaf_count` leaves
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo)]
+pub struct MmrAncestryProof<MerkleHash> {
+    /// Number of leaves in the older MMR
+    pub prev_leaf_count: u64,
+    /// Number of leaves in the newer MMR
+    pub leaf_count: u64,
+    /// Peaks of the older MMR, from left to right
+    pub prev_peaks: Vec<MerkleHash>,
+    /// Roots of the subtrees appended since the older MMR, depth first and
+    /// left to right
+    pub proof_items: Vec<MerkleHash>,
+}
+
+/// Append-only Merkle Mountain Range
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MerkleMountainRange<H: Hash> {
+    /// `nodes[h][i]` is the root of the `i`-th perfect subtree of height `h`
+    nodes: Vec<Vec<H::Output>>,
+}
+
+impl<H: Hash> Default for MerkleMountainRange<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H: Hash> MerkleMountainRange<H> {
+    /// Create an empty MMR
+    pub fn new() -> Self {
+        Self { nodes: vec![Vec::new()] }
+    }
+
+    /// Append an item and return its leaf index
+    pub fn append<T: Encode>(&mut self, item: &T) -> u64 {
+        self.append_leaf(hash_leaf::<H>(&item.encode()))
+    }
+
+    /// Append an already hashed leaf and return its leaf index
+    pub fn append_leaf(&mut self, leaf: H::Output) -> u64 {
+        self.nodes[0].push(leaf);
+
+        let mut height = 0;
+        while self.nodes[height].len() & 1 == 0 {
+            let level = &self.nodes[height];
+            let parent = hash_node::<H>(&level[level.len() - 2], &level[level.len() - 1]);
+            if self.nodes.len() == height + 1 {
+                self.nodes.push(Vec::new());
+            }
+            self.nodes[height + 1].push(parent);
+            height += 1;
+        }
+
+        self.leaf_count() - 1
+    }
+
+    /// Number of leaves appended so far
+    pub fn leaf_count(&self) -> u64 {
+        self.nodes[0].len() as u64
+    }
+
+    /// Current peaks, from left to right
+    pub fn peaks(&self) -> Vec<H::Output> {
+        self.peaks_at(self.leaf_count()).unwrap_or_default()
+    }
+
+    /// Current MMR root
+    pub fn root(&self) -> H::Output {
+        bag_peaks::<H>(self.leaf_count(), &self.peaks())
+    }
+
+    /// Peaks of the MMR as it was when it had `leaf_count` leaves
+    pub fn peaks_at(&self, leaf_count: u64) -> Option<Vec<H::Output>> {
+        if leaf_count > self.leaf_count() {
+            return None;
+        }
+
+        Some(mountains(leaf_count).map(|(start, height)| self.subtree(start, height)).collect())
+    }
+
+    /// MMR root as it was when it had `leaf_count` leaves
+    pub fn root_at(&self, leaf_count: u64) -> Option<H::Output> {
+        Some(bag_peaks::<H>(leaf_count, &self.peaks_at(leaf_count)?))
+    }
+
+    /// Prove that `leaf_index` is included in the MMR of `leaf_count` leaves
+    pub fn inclusion_proof(&self, leaf_index: u64, leaf_count: u64) -> Option<MmrProof<H::Output>> {
+        if leaf_index >= leaf_count || leaf_count > self.leaf_count() {
+            return None;
+        }
+
+        let mut proof_path = Vec::new();
+        let mut peaks = Vec::new();
+        for (start, height) in mountains(leaf_count) {
+            if (start..start + (1 << height)).contains(&leaf_index) {
+                proof_path = (0..height)
+                    .map(|h| self.nodes[h as usize][((leaf_index >> h) ^ 1) as usize])
+                    .collect();
+            } else {
+                peaks.push(self.subtree(start, height));
+            }
+        }
+
+        Some(MmrProof { leaf_count, leaf_index, proof_path, peaks })
+    }
+
+    /// Prove that the MMR of `prev_leaf_count` leaves is a prefix of the MMR
+    /// of `leaf_count` leaves
+    pub fn ancestry_proof(
+        &self,
+        prev_leaf_count: u64,
+        leaf_count: u64,
+    ) -> Option<MmrAncestryProof<H::Output>> {
+        if prev_leaf_count > leaf_count || leaf_count > self.leaf_count() {
+            return None;
+        }
+
+        let mut proof_items = Vec::new();
+        for (start, height) in mountains(leaf_count) {
+            self.collect_appended(start, height, prev_leaf_count, &mut proof_items);
+        }
+
+        Some(MmrAncestryProof {
+            prev_leaf_count,
+            leaf_count,
+            prev_peaks: self.peaks_at(prev_leaf_count)?,
+            proof_items,
+        })
+    }
+
+    /// Root of the perfect subtree of `height` starting at leaf `start`
+    fn subtree(&self, start: u64, height: u32) -> H::Output {
+        self.nodes[height as usize][(start >> height) as usize]
+    }
+
+    /// Collect the roots of the maximal subtrees of `(start, height)` that lie
+    /// entirely at or after `prev_leaf_count`
+    fn collect_appended(&self, start: u64, height: u32, prev_leaf_count: u64, out: &mut Vec<H::Output>) {
+        if start >= prev_leaf_count {
+            out.push(self.subtree(start, height));
+        } else if start + (1 << height) > prev_leaf_count {
+            let half = 1 << (height - 1);
+            self.collect_appended(start, height - 1, prev_leaf_count, out);
+            self.collect_appended(start + half, height - 1, prev_leaf_count, out);
+        }
+    }
+}
+
+/// Verify that a leaf is included in the MMR with the given root
+pub fn verify_mmr_proof<H: Hash>(
+    proof: &MmrProof<<H as Hash>::Output>,
+    leaf: <H as Hash>::Output,
+    root: <H as Hash>::Output,
+) -> bool {
+    if proof.leaf_index >= proof.leaf_count {
+        return false;
+    }
+
+    let mut other_peaks = proof.peaks.iter();
+    let mut peaks = Vec::new();
+    for (start, height) in mountains(proof.leaf_count) {
+        if (start..start + (1 << height)).contains(&proof.leaf_index) {
+            if proof.proof_path.len() != height as usize {
+                return false;
+            }
+            let local_index = proof.leaf_index - start;
+            match crate::compute_merkle_root::<H>(leaf, local_index, &proof.proof_path) {
+                Some(peak) => peaks.push(peak),
+                None => return false,
+            }
+        } else {
+            match other_peaks.next() {
+                Some(peak) => peaks.push(*peak),
+                None => return false,
+            }
+        }
+    }
+
+    other_peaks.next().is_none() && bag_peaks::<H>(proof.leaf_count, &peaks) == root
+}
+
+/// Verify that `prev_root` is an ancestor of `root`
+pub fn verify_mmr_ancestry<H: Hash>(
+    proof: &MmrAncestryProof<<H as Hash>::Output>,
+    prev_root: <H as Hash>::Output,
+    root: <H as Hash>::Output,
+) -> bool {
+    if proof.prev_leaf_count > proof.leaf_count
+        || proof.prev_peaks.len() != mountains(proof.prev_leaf_count).count()
+        || bag_peaks::<H>(proof.prev_leaf_count, &proof.prev_peaks) != prev_root
+    {
+        return false;
+    }
+
+    let prev_mountains: Vec<(u64, u32)> = mountains(proof.prev_leaf_count).collect();
+    let mut proof_items = proof.proof_items.iter();
+    let mut peaks = Vec::new();
+    for (start, height) in mountains(proof.leaf_count) {
+        let rebuilt = rebuild_subtree::<H>(
+            start,
+            height,
+            &prev_mountains,
+            &proof.prev_peaks,
+            proof.prev_leaf_count,
+            &mut proof_items,
+        );
+        match rebuilt {
+            Some(peak) => peaks.push(peak),
+            None => return false,
+        }
+    }
+
+    proof_items.next().is_none() && bag_peaks::<H>(proof.leaf_count, &peaks) == root
+}
+
+/// Recompute the root of subtree `(start, height)` from the older peaks and
+/// the appended subtree roots
+fn rebuild_subtree<'a, H: Hash>(
+    start: u64,
+    height: u32,
+    prev_mountains: &[(u64, u32)],
+    prev_peaks: &[H::Output],
+    prev_leaf_count: u64,
+    proof_items: &mut impl Iterator<Item = &'a H::Output>,
+) -> Option<H::Output>
+where
+    H::Output: 'a,
+{
+    if start >= prev_leaf_count {
+        return proof_items.next().copied();
+    }
+    if let Some(i) = prev_mountains.iter().position(|m| *m == (start, height)) {
+        return Some(prev_peaks[i]);
+    }
+    if height == 0 {
+        return None;
+    }
+
+    let half = 1 << (height - 1);
+    let left = rebuild_subtree::<H>(start, height - 1, prev_mountains, prev_peaks, prev_leaf_count, proof_items)?;
+    let right = rebuild_subtree::<H>(start + half, height - 1, prev_mountains, prev_peaks, prev_leaf_count, proof_items)?;
+    Some(hash_node::<H>(&left, &right))
+}
+
+/// Commit to the peaks and leaf count of an MMR
+///
+/// Peaks are folded right to left with the node hash, and the result is
+/// hashed together with the leaf count. An empty MMR has the default root.
+pub fn bag_peaks<H: Hash>(leaf_count: u64, peaks: &[<H as Hash>::Output]) -> <H as Hash>::Output {
+    let Some((last, rest)) = peaks.split_last() else {
+        return Default::default();
+    };
+    let bagged = rest.iter().rev().fold(*last, |acc, peak| hash_node::<H>(peak, &acc));
+
+    let mut buf = vec![MMR_ROOT_PREFIX];
+    leaf_count.encode_to(&mut buf);
+    buf.extend_from_slice(bagged.as_ref());
+    <H as Hash>::hash(&buf)
+}
+
+/// `(first leaf, height)` of each mountain of an MMR with `leaf_count`
+/// leaves, from left to right
+fn mountains(leaf_count: u64) -> impl Iterator<Item = (u64, u32)> {
+    (0..u64::BITS).rev().filter(move |h| leaf_count & (1 << h) != 0).scan(0u64, |start, h| {
+        let mountain = (*start, h);
+        *start += 1 << h;
+        Some(mountain)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_runtime::traits::BlakeTwo256;
+
+    type Mmr = MerkleMountainRange<BlakeTwo256>;
+
+    fn build(size: u64) -> Mmr {
+        let mut mmr = Mmr::new();
+        for i in 0..size {
+            assert_eq!(mmr.append(&i), i);
+        }
+        mmr
+    }
+
+    #[test]
+    fn test_peaks_follow_leaf_count_bits() {
+        let mmr = build(11);
+        assert_eq!(mmr.peaks().len(), 3);
+        assert_eq!(mmr.peaks()[0], crate::MerkleTree::<BlakeTwo256>::new(&(0..8u64).collect::<Vec<_>>()).root());
+        assert_eq!(Mmr::new().root(), Default::default());
+    }
+
+    #[test]
+    fn test_historical_roots_stable() {
+        let mut mmr = Mmr::new();
+        let mut roots = vec![mmr.root()];
+        for i in 0..20u64 {
+            mmr.append(&i);
+            roots.push(mmr.root());
+        }
+        for (size, root) in roots.iter().enumerate() {
+            assert_eq!(mmr.root_at(size as u64), Some(*root));
+        }
+        assert!(mmr.root_at(21).is_none());
+    }
+
+    #[test]
+    fn test_inclusion_against_historical_roots() {
+        let mmr = build(20);
+        for size in 1..=20 {
+            let root = mmr.root_at(size).unwrap();
+            for index in 0..size {
+                let leaf = hash_leaf::<BlakeTwo256>(&index.encode());
+                let proof = mmr.inclusion_proof(index, size).unwrap();
+                assert!(verify_mmr_proof::<BlakeTwo256>(&proof, leaf, root));
+
+                let wrong = hash_leaf::<BlakeTwo256>(&(index + 1).encode());
+                assert!(!verify_mmr_proof::<BlakeTwo256>(&proof, wrong, root));
+            }
+        }
+        assert!(mmr.inclusion_proof(5, 5).is_none());
+        assert!(mmr.inclusion_proof(0, 21).is_none());
+    }
+
+    #[test]
+    fn test_inclusion_tampering_rejected() {
+        let mmr = build(13);
+        let root = mmr.root();
+        let leaf = hash_leaf::<BlakeTwo256>(&9u64.encode());
+        let proof = mmr.inclusion_proof(9, 13).unwrap();
+
+        let mut tampered = proof.clone();
+        tampered.leaf_index = 8;
+        assert!(!verify_mmr_proof::<BlakeTwo256>(&tampered, leaf, root));
+
+        let mut tampered = proof.clone();
+        tampered.leaf_count = 14;
+        assert!(!verify_mmr_proof::<BlakeTwo256>(&tampered, leaf, root));
+
+        let mut tampered = proof.clone();
+        tampered.peaks[0].as_mut()[0] ^= 0x01;
+        assert!(!verify_mmr_proof::<BlakeTwo256>(&tampered, leaf, root));
+
+        let mut tampered = proof;
+        tampered.proof_path[0].as_mut()[0] ^= 0x01;
+        assert!(!verify_mmr_proof::<BlakeTwo256>(&tampered, leaf, root));
+    }
+
+    #[test]
+    fn test_ancestry_between_all_sizes() {
+        let mmr = build(24);
+        for size in 0..=24 {
+            let root = mmr.root_at(size).unwrap();
+            for prev in 0..=size {
+                let prev_root = mmr.root_at(prev).unwrap();
+                let proof = mmr.ancestry_proof(prev, size).unwrap();
+                assert!(verify_mmr_ancestry::<BlakeTwo256>(&proof, prev_root, root));
+            }
+        }
+        assert!(mmr.ancestry_proof(5, 4).is_none());
+        assert!(mmr.ancestry_proof(4, 25).is_none());
+    }
+
+    #[test]
+    fn test_ancestry_rejects_forked_history() {
+        let mmr = build(12);
+        let mut fork = build(7);
+        for i in 100..105u64 {
+            fork.append(&i);
+        }
+
+        let proof = fork.ancestry_proof(7, 12).unwrap();
+        assert!(verify_mmr_ancestry::<BlakeTwo256>(&proof, mmr.root_at(7).unwrap(), fork.root()));
+        assert!(!verify_mmr_ancestry::<BlakeTwo256>(&proof, mmr.root_at(7).unwrap(), mmr.root()));
+
+        let proof = mmr.ancestry_proof(9, 12).unwrap();
+        assert!(!verify_mmr_ancestry::<BlakeTwo256>(&proof, fork.root_at(9).unwrap(), mmr.root()));
+
+        let mut tampered = proof.clone();
+        tampered.proof_items.pop();
+        assert!(!verify_mmr_ancestry::<BlakeTwo256>(&tampered, mmr.root_at(9).unwrap(), mmr.root()));
+    }
+}
@@ -0,0 +1,422 @@
+//! Sparse Merkle tree for keyed JAM state
+//!
+//! Keys are 256-bit hashes selecting one of 2^256 leaf positions, most
+//! significant bit first. Empty subtrees hash to the default (all-zero)
+//! hash at every depth, and a subtree holding a single leaf hashes to that
+//! leaf, so only the levels where keys diverge are ever hashed. A proof can
+//! show that a key is absent, e.g. that an operation hash has never been
+//! executed, by ending in an empty subtree or in the single leaf of another
+//! key sharing its prefix. Proofs carry a bitmap of which siblings are
+//! non-empty and only those siblings, which keeps them within a fixed
+//! `MaxEncodedLen` bound.
+
+use sp_std::{cell::Cell, collections::btree_map::BTreeMap, prelude::*};
+use sp_runtime::{traits::{ConstU32, Hash}, BoundedVec};
+use scale_info::TypeInfo;
+use codec::{Encode, Decode, MaxEncodedLen};
+
+use crate::{hash_leaf, hash_node};
+
+/// Depth of the sparse Merkle tree, in bits of the key
+pub const SMT_DEPTH: usize = 256;
+
+/// Compact sparse Merkle proof for one key
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+pub struct SparseMerkleProof<MerkleHash> {
+    /// Bit `i` (most significant bit of byte 0 first) is set if the sibling
+    /// at depth `i` below the root is non-empty
+    pub sibling_bitmap: [u8; SMT_DEPTH / 8],
+    /// Non-empty siblings, from the root down
+    pub siblings: BoundedVec<MerkleHash, ConstU32<{ SMT_DEPTH as u32 }>>,
+    /// Key and value hash of the leaf the path of an absent key ends in,
+    /// if it does not end in an empty subtree
+    pub other_leaf: Option<(MerkleHash, MerkleHash)>,
+}
+
+/// Sparse Merkle tree keyed by 256-bit hashes
+#[derive(Clone, Debug)]
+pub struct SparseMerkleTree<H: Hash> {
+    /// Values by key
+    leaves: BTreeMap<H::Output, Vec<u8>>,
+    /// Keys and value hashes sorted by key bits
+    sorted: Vec<(H::Output, H::Output)>,
+    /// Root, computed on first use after a change
+    root: Cell<Option<H::Output>>,
+}
+
+impl<H: Hash> PartialEq for SparseMerkleTree<H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.leaves == other.leaves
+    }
+}
+
+impl<H: Hash> Eq for SparseMerkleTree<H> {}
+
+impl<H: Hash> Default for SparseMerkleTree<H>
+where
+    H::Output: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H: Hash> SparseMerkleTree<H>
+where
+    H::Output: Ord,
+{
+    /// Create an empty tree
+    pub fn new() -> Self {
+        Self { leaves: BTreeMap::new(), sorted: Vec::new(), root: Cell::new(None) }
+    }
+
+    /// Insert a value, returning the previous value of the key
+    pub fn insert(&mut self, key: H::Output, value: Vec<u8>) -> Option<Vec<u8>> {
+        let entry = (key, <H as Hash>::hash(&value));
+        match self.position(&key) {
+            Ok(index) => self.sorted[index] = entry,
+            Err(index) => self.sorted.insert(index, entry),
+        }
+        self.root.set(None);
+        self.leaves.insert(key, value)
+    }
+
+    /// Remove a key, returning its value
+    pub fn remove(&mut self, key: &H::Output) -> Option<Vec<u8>> {
+        let value = self.leaves.remove(key)?;
+        if let Ok(index) = self.position(key) {
+            self.sorted.remove(index);
+        }
+        self.root.set(None);
+        Some(value)
+    }
+
+    /// Value of a key
+    pub fn get(&self, key: &H::Output) -> Option<&Vec<u8>> {
+        self.leaves.get(key)
+    }
+
+    /// Number of keys in the tree
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Whether the tree is empty
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Tree root, or the default hash for an empty tree
+    pub fn root(&self) -> H::Output {
+        if let Some(root) = self.root.get() {
+            return root;
+        }
+        let root = subtree_root::<H>(&self.sorted, 0);
+        self.root.set(Some(root));
+        root
+    }
+
+    /// Proof for `key`, proving its value if present and its absence
+    /// otherwise
+    pub fn proof(&self, key: &H::Output) -> SparseMerkleProof<H::Output> {
+        let mut sibling_bitmap = [0u8; SMT_DEPTH / 8];
+        let mut siblings = Vec::new();
+
+        // Distinct keys diverge before `SMT_DEPTH`, so the path ends in an
+        // empty or single-leaf subtree by then
+        let mut leaves = &self.sorted[..];
+        let mut depth = 0;
+        while leaves.len() > 1 {
+            let split = leaves.partition_point(|(k, _)| !bit(k.as_ref(), depth));
+            let (left, right) = leaves.split_at(split);
+            let (path, other) = if bit(key.as_ref(), depth) { (right, left) } else { (left, right) };
+
+            let sibling = subtree_root::<H>(other, depth + 1);
+            if sibling != Default::default() {
+                sibling_bitmap[depth / 8] |= 0x80 >> (depth % 8);
+                siblings.push(sibling);
+            }
+            leaves = path;
+            depth += 1;
+        }
+
+        SparseMerkleProof {
+            sibling_bitmap,
+            // Cannot exceed the bound: at most one sibling per depth
+            siblings: BoundedVec::truncate_from(siblings),
+            other_leaf: leaves.first().filter(|(k, _)| k != key).copied(),
+        }
+    }
+
+    /// Position of `key` in the sorted keys, or where it would be inserted
+    fn position(&self, key: &H::Output) -> Result<usize, usize> {
+        self.sorted.binary_search_by(|(k, _)| k.as_ref().cmp(key.as_ref()))
+    }
+}
+
+/// Root of the subtree at `depth` holding `leaves`, which all share the key
+/// prefix leading to it
+fn subtree_root<H: Hash>(leaves: &[(H::Output, H::Output)], depth: usize) -> H::Output {
+    match leaves {
+        [] => Default::default(),
+        [(key, value_hash)] => smt_leaf::<H>(key, value_hash),
+        _ => {
+            let split = leaves.partition_point(|(k, _)| !bit(k.as_ref(), depth));
+            let (left, right) = leaves.split_at(split);
+            hash_node::<H>(&subtree_root::<H>(left, depth + 1), &subtree_root::<H>(right, depth + 1))
+        }
+    }
+}
+
+/// Verify that `key` holds `value` in the tree with the given root
+pub fn verify_smt_inclusion<H: Hash>(
+    proof: &SparseMerkleProof<<H as Hash>::Output>,
+    key: &<H as Hash>::Output,
+    value: &[u8],
+    root: <H as Hash>::Output,
+) -> bool {
+    let leaf = smt_leaf::<H>(key, &<H as Hash>::hash(value));
+    proof.other_leaf.is_none() && compute_smt_root::<H>(proof, key, leaf) == Some(root)
+}
+
+/// Verify that `key` is absent from the tree with the given root
+pub fn verify_smt_non_inclusion<H: Hash>(
+    proof: &SparseMerkleProof<<H as Hash>::Output>,
+    key: &<H as Hash>::Output,
+    root: <H as Hash>::Output,
+) -> bool {
+    let leaf = match &proof.other_leaf {
+        None => Default::default(),
+        Some((other_key, value_hash)) => {
+            // The other leaf must sit where the path of `key` ends
+            let depth = path_length(proof);
+            if other_key == key
+                || other_key.as_ref().len() != key.as_ref().len()
+                || (0..depth).any(|d| bit(other_key.as_ref(), d) != bit(key.as_ref(), d))
+            {
+                return false;
+            }
+            smt_leaf::<H>(other_key, value_hash)
+        }
+    };
+    compute_smt_root::<H>(proof, key, leaf) == Some(root)
+}
+
+/// Depth the path of a proof ends at, just below its deepest non-empty
+/// sibling
+fn path_length<MerkleHash>(proof: &SparseMerkleProof<MerkleHash>) -> usize {
+    (0..SMT_DEPTH).rev().find(|depth| bit(&proof.sibling_bitmap, *depth)).map_or(0, |depth| depth + 1)
+}
+
+/// Recompute the root from the hash of the subtree the path of `key` ends
+/// in and its compact proof
+fn compute_smt_root<H: Hash>(
+    proof: &SparseMerkleProof<<H as Hash>::Output>,
+    key: &<H as Hash>::Output,
+    leaf: <H as Hash>::Output,
+) -> Option<<H as Hash>::Output> {
+    let key = key.as_ref();
+    let non_empty = (0..SMT_DEPTH).filter(|depth| bit(&proof.sibling_bitmap, *depth)).count();
+    if key.len() * 8 != SMT_DEPTH || non_empty != proof.siblings.len() {
+        return None;
+    }
+
+    let mut siblings = proof.siblings.iter().rev();
+    let mut node = leaf;
+    for depth in (0..path_length(proof)).rev() {
+        let sibling = if bit(&proof.sibling_bitmap, depth) {
+            let sibling = *siblings.next()?;
+            if sibling == Default::default() {
+                return None;
+            }
+            sibling
+        } else {
+            Default::default()
+        };
+        node = if bit(key, depth) {
+            hash_node::<H>(&sibling, &node)
+        } else {
+            hash_node::<H>(&node, &sibling)
+        };
+    }
+
+    Some(node)
+}
+
+/// Leaf hash committing to a key and the hash of its value
+fn smt_leaf<H: Hash>(key: &<H as Hash>::Output, value_hash: &<H as Hash>::Output) -> <H as Hash>::Output {
+    let mut data = key.as_ref().to_vec();
+    data.extend_from_slice(value_hash.as_ref());
+    hash_leaf::<H>(&data)
+}
+
+/// Bit `index` of `bytes`, most significant bit first
+fn bit(bytes: &[u8], index: usize) -> bool {
+    bytes[index / 8] & (0x80 >> (index % 8)) != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_runtime::testing::H256;
+    use sp_runtime::traits::BlakeTwo256;
+
+    type Smt = SparseMerkleTree<BlakeTwo256>;
+
+    fn key(n: u8) -> H256 {
+        BlakeTwo256::hash(&[n])
+    }
+
+    fn tree(keys: &[u8]) -> Smt {
+        let mut smt = Smt::new();
+        for n in keys {
+            smt.insert(key(*n), vec![*n]);
+        }
+        smt
+    }
+
+    #[test]
+    fn test_root_independent_of_order() {
+        assert_eq!(Smt::new().root(), H256::zero());
+        assert_eq!(tree(&[1, 2, 3, 4]).root(), tree(&[4, 2, 1, 3]).root());
+        assert_ne!(tree(&[1, 2, 3]).root(), tree(&[1, 2, 4]).root());
+    }
+
+    #[test]
+    fn test_insert_and_delete() {
+        let mut smt = tree(&[1, 2]);
+        let before = smt.root();
+
+        assert_eq!(smt.insert(key(3), vec![3]), None);
+        assert_ne!(smt.root(), before);
+        assert_eq!(smt.insert(key(3), vec![30]), Some(vec![3]));
+        assert_eq!(smt.get(&key(3)), Some(&vec![30]));
+
+        assert_eq!(smt.remove(&key(3)), Some(vec![30]));
+        assert_eq!(smt.root(), before);
+        assert_eq!(smt.len(), 2);
+    }
+
+    #[test]
+    fn test_inclusion_proofs() {
+        let smt = tree(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        let root = smt.root();
+        for n in 1..=8u8 {
+            let proof = smt.proof(&key(n));
+            assert!(verify_smt_inclusion::<BlakeTwo256>(&proof, &key(n), &[n], root));
+            assert!(!verify_smt_inclusion::<BlakeTwo256>(&proof, &key(n), &[n + 1], root));
+            assert!(!verify_smt_non_inclusion::<BlakeTwo256>(&proof, &key(n), root));
+        }
+    }
+
+    #[test]
+    fn test_non_inclusion_proofs() {
+        let smt = tree(&[1, 2, 3, 4, 5]);
+        let root = smt.root();
+        for n in 6..=20u8 {
+            let proof = smt.proof(&key(n));
+            assert!(verify_smt_non_inclusion::<BlakeTwo256>(&proof, &key(n), root));
+            assert!(!verify_smt_inclusion::<BlakeTwo256>(&proof, &key(n), &[n], root));
+            assert!(!verify_smt_non_inclusion::<BlakeTwo256>(&proof, &key(1), root));
+        }
+
+        let empty = Smt::new();
+        let proof = empty.proof(&key(1));
+        assert!(proof.siblings.is_empty());
+        assert!(verify_smt_non_inclusion::<BlakeTwo256>(&proof, &key(1), empty.root()));
+    }
+
+    #[test]
+    fn test_proof_tampering_rejected() {
+        let smt = tree(&[1, 2, 3, 4, 5, 6]);
+        let root = smt.root();
+        let proof = smt.proof(&key(3));
+
+        let mut tampered = proof.clone();
+        tampered.siblings[0].as_mut()[0] ^= 0x01;
+        assert!(!verify_smt_inclusion::<BlakeTwo256>(&tampered, &key(3), &[3], root));
+
+        let mut tampered = proof.clone();
+        let first = (0..SMT_DEPTH).find(|d| bit(&proof.sibling_bitmap, *d)).unwrap();
+        tampered.sibling_bitmap[first / 8] ^= 0x80 >> (first % 8);
+        assert!(!verify_smt_inclusion::<BlakeTwo256>(&tampered, &key(3), &[3], root));
+
+        let mut tampered = proof;
+        tampered.siblings.try_push(H256::repeat_byte(1)).unwrap();
+        assert!(!verify_smt_inclusion::<BlakeTwo256>(&tampered, &key(3), &[3], root));
+    }
+
+    #[test]
+    fn test_single_leaf_subtrees_short_circuit() {
+        let smt = tree(&[1]);
+        assert_eq!(smt.root(), smt_leaf::<BlakeTwo256>(&key(1), &BlakeTwo256::hash(&[1])));
+
+        let proof = smt.proof(&key(2));
+        assert!(proof.siblings.is_empty());
+        assert_eq!(proof.other_leaf, Some((key(1), BlakeTwo256::hash(&[1]))));
+        assert!(verify_smt_non_inclusion::<BlakeTwo256>(&proof, &key(2), smt.root()));
+    }
+
+    #[test]
+    fn test_non_inclusion_other_leaf_checked() {
+        let smt = tree(&[1, 2, 3, 4, 5]);
+        let root = smt.root();
+        let absent = (6..=40u8).map(key).find(|k| smt.proof(k).other_leaf.is_some()).unwrap();
+        let proof = smt.proof(&absent);
+        assert!(verify_smt_non_inclusion::<BlakeTwo256>(&proof, &absent, root));
+
+        // A present key cannot be passed off as another key's leaf
+        let (present, value_hash) = proof.other_leaf.unwrap();
+        assert!(!verify_smt_non_inclusion::<BlakeTwo256>(&proof, &present, root));
+
+        // Nor can a leaf off the path of the absent key
+        let off_path = (1..=5u8).map(key).find(|k| bit(k.as_ref(), 0) != bit(absent.as_ref(), 0)).unwrap();
+        let mut tampered = proof.clone();
+        tampered.other_leaf = Some((off_path, value_hash));
+        assert!(!verify_smt_non_inclusion::<BlakeTwo256>(&tampered, &absent, root));
+
+        // Inclusion proofs end at the key's own leaf
+        let mut tampered = smt.proof(&present);
+        tampered.other_leaf = Some((present, value_hash));
+        assert!(!verify_smt_inclusion::<BlakeTwo256>(&tampered, &present, &smt.get(&present).unwrap()[..], root));
+    }
+
+    #[test]
+    fn test_cached_root_follows_changes() {
+        let mut smt = tree(&[1, 2, 3]);
+        let before = smt.root();
+        smt.insert(key(4), vec![4]);
+        assert_eq!(smt.root(), tree(&[1, 2, 3, 4]).root());
+        smt.insert(key(4), vec![40]);
+        assert_ne!(smt.root(), tree(&[1, 2, 3, 4]).root());
+        assert_eq!(smt.remove(&key(5)), None);
+        smt.remove(&key(4));
+        assert_eq!(smt.root(), before);
+        assert_eq!(smt, tree(&[3, 2, 1]));
+    }
+
+    #[test]
+    fn test_proof_encoding_bounded_and_compact() {
+        let smt = tree(&(0..64).collect::<Vec<u8>>());
+        let proof = smt.proof(&key(7));
+
+        assert!(proof.encoded_size() <= SparseMerkleProof::<H256>::max_encoded_len());
+        // With 64 random keys only the top few levels have non-empty siblings
+        assert!(proof.siblings.len() < 16);
+        assert_eq!(SparseMerkleProof::<H256>::decode(&mut &proof.encode()[..]).unwrap(), proof);
+    }
+
+    #[test]
+    fn test_replay_protection() {
+        let mut executed = Smt::new();
+        let operation = BlakeTwo256::hash(b"jam operation");
+
+        let proof = executed.proof(&operation);
+        assert!(verify_smt_non_inclusion::<BlakeTwo256>(&proof, &operation, executed.root()));
+
+        executed.insert(operation, Vec::new());
+        let stale_root = executed.root();
+        assert!(!verify_smt_non_inclusion::<BlakeTwo256>(&proof, &operation, stale_root));
+        assert!(verify_smt_inclusion::<BlakeTwo256>(&executed.proof(&operation), &operation, &[], stale_root));
+    }
+}
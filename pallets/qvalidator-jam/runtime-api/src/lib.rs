@@ -0,0 +1,25 @@
+//! Runtime API for the QValidator JAM pallet
+//!
+//! Exposes the per-block JAM state roots and inclusion proofs of finalized
+//! operations, so light clients can bind a `JamProof` to chain state.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use jam_quantum_bridge::JamProof;
+
+sp_api::decl_runtime_apis! {
+    /// JAM state commitments of finalized operations
+    pub trait QValidatorJamApi<Hash, BlockNumber>
+    where
+        Hash: Codec,
+        BlockNumber: Codec,
+    {
+        /// JAM state root committed at `block_number`
+        fn jam_state_root(block_number: BlockNumber) -> Option<Hash>;
+
+        /// Inclusion proof of `operation_hash` in the JAM state root of the
+        /// block that finalized it
+        fn jam_state_proof(block_number: BlockNumber, operation_hash: Hash) -> Option<JamProof<Hash, Hash>>;
+    }
+}
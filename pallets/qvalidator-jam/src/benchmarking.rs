@@ -0,0 +1,234 @@
+//! Benchmarks for the QValidator JAM pallet
+
+use super::*;
+use crate::Pallet as QValidatorJam;
+use frame_benchmarking::v2::*;
+use frame_support::{
+    traits::{Get, Hooks},
+    BoundedVec,
+};
+use frame_system::{pallet_prelude::BlockNumberFor, RawOrigin};
+use jam_quantum_bridge::{compute_merkle_root, hash_leaf, JamOperationStatus, JamOperationType};
+use sp_runtime::traits::{Hash, Saturating};
+use sp_std::{vec, vec::Vec};
+
+const SEED: u32 = 0;
+
+/// Trusted proof with a path of `path_length` siblings over `data_size`
+/// bytes of justified data
+fn trusted_proof<T: Config>(path_length: u32, data_size: u32) -> BoundedJamProof<T> {
+    let proof_path: Vec<T::Hash> = (0..path_length).map(|index| T::Hashing::hash_of(&index)).collect();
+    let justified_data = vec![1; data_size as usize];
+    let leaf = hash_leaf::<T::Hashing>(&justified_data);
+    let merkle_root = compute_merkle_root::<T::Hashing>(leaf, 0, &proof_path).expect("leaf index is in range");
+    let block_hash = T::Hashing::hash_of(&(path_length, data_size));
+    TrustedRoots::<T>::insert(block_hash, (merkle_root, frame_system::Pallet::<T>::block_number()));
+
+    BoundedJamProof {
+        block_hash,
+        merkle_root,
+        proof_path: proof_path.try_into().expect("path length is bounded"),
+        leaf_index: 0,
+        justified_data: justified_data.try_into().expect("data size is bounded"),
+    }
+}
+
+/// Largest operation data a proof can justify
+fn max_justified_data<T: Config>() -> u32 {
+    T::MaxOperationData::get().min(T::MaxJustifiedData::get())
+}
+
+/// Store a sealed operation of the largest justifiable size carrying
+/// `proofs` trusted proofs of its data with the maximum path length
+fn insert_operation<T: Config>(
+    index: u32,
+    initiator: T::AccountId,
+    status: JamOperationStatus,
+    proofs: u32,
+) -> T::Hash {
+    let now = frame_system::Pallet::<T>::block_number();
+    let data_size = max_justified_data::<T>();
+    let proof = trusted_proof::<T>(T::MaxProofPathLength::get(), data_size);
+    let operation_hash = T::Hashing::hash_of(&(b"operation", index));
+    Operations::<T>::insert(
+        operation_hash,
+        JamOperationInfo {
+            initiator,
+            target_parachain_id: 2000,
+            operation_type: JamOperationType::MessagePassing,
+            data: vec![1; data_size as usize].try_into().expect("data size is bounded"),
+            created_at: now,
+            expires_at: now.saturating_add(10u32.into()),
+            proofs: vec![proof; proofs as usize].try_into().expect("proof count is bounded"),
+            sealed: true,
+            status,
+        },
+    );
+    operation_hash
+}
+
+/// Fill a bounded list of operation hashes with `count` placeholders
+fn placeholders<T: Config>(count: u32) -> BoundedVec<T::Hash, T::MaxActiveOperations> {
+    (0..count)
+        .map(|index| T::Hashing::hash_of(&(b"placeholder", index)))
+        .collect::<Vec<_>>()
+        .try_into()
+        .expect("count is bounded")
+}
+
+/// Active operation in a full active set, with the block's finalized
+/// operations one short of the bound
+fn finalizable_operation<T: Config>(initiator: T::AccountId, status: JamOperationStatus) -> T::Hash {
+    let max = T::MaxActiveOperations::get();
+    let operation_hash = insert_operation::<T>(0, initiator, status, T::MaxProofs::get());
+    let mut active = placeholders::<T>(max - 1);
+    active.try_push(operation_hash).expect("one slot is left");
+    ActiveOperations::<T>::put(active);
+    FinalizedOperations::<T>::insert(frame_system::Pallet::<T>::block_number(), placeholders::<T>(max - 1));
+    operation_hash
+}
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    /// Requires `Config::Executor` to handle message passing operations
+    #[benchmark]
+    fn submit_operation(d: Linear<1, { T::MaxOperationData::get() }>) {
+        ActiveOperations::<T>::put(placeholders::<T>(T::MaxActiveOperations::get() - 1));
+        let caller: T::AccountId = whitelisted_caller();
+        let expires_at = frame_system::Pallet::<T>::block_number().saturating_add(10u32.into());
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), 2000, JamOperationType::MessagePassing, vec![1; d as usize], expires_at);
+
+        assert_eq!(ActiveOperations::<T>::get().len() as u32, T::MaxActiveOperations::get());
+    }
+
+    #[benchmark]
+    fn submit_proof(l: Linear<0, { T::MaxProofPathLength::get() }>, j: Linear<0, { T::MaxJustifiedData::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let proofs = T::MaxProofs::get() - 1;
+        let operation_hash = insert_operation::<T>(0, caller.clone(), JamOperationStatus::Pending, proofs);
+        Operations::<T>::mutate(operation_hash, |operation| operation.as_mut().unwrap().sealed = false);
+        let proof = trusted_proof::<T>(l, j);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), operation_hash, proof);
+
+        assert_eq!(Operations::<T>::get(operation_hash).unwrap().proofs.len() as u32, proofs + 1);
+    }
+
+    #[benchmark]
+    fn cancel_operation() {
+        let caller: T::AccountId = whitelisted_caller();
+        let operation_hash = finalizable_operation::<T>(caller.clone(), JamOperationStatus::Pending);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), operation_hash);
+
+        assert_eq!(Operations::<T>::get(operation_hash).unwrap().status, JamOperationStatus::Failed);
+    }
+
+    #[benchmark]
+    fn seal_operation() {
+        let caller: T::AccountId = whitelisted_caller();
+        let operation_hash =
+            insert_operation::<T>(0, caller.clone(), JamOperationStatus::Pending, T::MaxProofs::get());
+        Operations::<T>::mutate(operation_hash, |operation| operation.as_mut().unwrap().sealed = false);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), operation_hash);
+
+        assert!(Operations::<T>::get(operation_hash).unwrap().sealed);
+    }
+
+    /// Import a root into a block already holding all but one import
+    #[benchmark]
+    fn import_merkle_root() -> Result<(), BenchmarkError> {
+        let origin = T::RelayerOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let now = frame_system::Pallet::<T>::block_number();
+        let imports = (1..T::MaxRootImports::get()).map(|index| T::Hashing::hash_of(&index)).collect::<Vec<_>>();
+        RootImports::<T>::insert(now, BoundedVec::try_from(imports).expect("count is bounded"));
+        let block_hash = T::Hashing::hash_of(&b"block");
+        let merkle_root = T::Hashing::hash_of(&b"root");
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, block_hash, merkle_root);
+
+        assert_eq!(TrustedRoots::<T>::get(block_hash), Some((merkle_root, now)));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn report_result() -> Result<(), BenchmarkError> {
+        let origin = T::RelayerOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let initiator: T::AccountId = account("initiator", 0, SEED);
+        let operation_hash = finalizable_operation::<T>(initiator, JamOperationStatus::InProgress);
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, operation_hash, true);
+
+        assert_eq!(Operations::<T>::get(operation_hash).unwrap().status, JamOperationStatus::Completed);
+        Ok(())
+    }
+
+    /// Verify and execute `a` pending operations carrying the maximum
+    /// number of proofs, and prune `p` operations and `r` imported roots
+    /// past retention
+    #[benchmark]
+    fn on_initialize(
+        a: Linear<0, { T::MaxActiveOperations::get() }>,
+        p: Linear<0, { T::MaxActiveOperations::get() }>,
+        r: Linear<0, { T::MaxRootImports::get() }>,
+    ) {
+        let initiator: T::AccountId = account("initiator", 0, SEED);
+        let active = (0..a)
+            .map(|index| insert_operation::<T>(index, initiator.clone(), JamOperationStatus::Pending, T::MaxProofs::get()))
+            .collect::<Vec<_>>();
+        ActiveOperations::<T>::put(BoundedVec::try_from(active).expect("count is bounded"));
+
+        let expired_at = frame_system::Pallet::<T>::block_number();
+        let pruned = (a..a + p)
+            .map(|index| insert_operation::<T>(index, initiator.clone(), JamOperationStatus::Completed, 0))
+            .collect::<Vec<_>>();
+        FinalizedOperations::<T>::insert(expired_at, BoundedVec::try_from(pruned).expect("count is bounded"));
+
+        let imported = (0..r).map(|index| T::Hashing::hash_of(&(b"block", index))).collect::<Vec<_>>();
+        for block_hash in &imported {
+            TrustedRoots::<T>::insert(block_hash, (T::Hashing::hash_of(block_hash), expired_at));
+        }
+        RootImports::<T>::insert(expired_at, BoundedVec::try_from(imported).expect("count is bounded"));
+
+        let now: BlockNumberFor<T> = expired_at.saturating_add(T::RetentionPeriod::get());
+        frame_system::Pallet::<T>::set_block_number(now);
+
+        #[block]
+        {
+            QValidatorJam::<T>::on_initialize(now);
+        }
+
+        assert!(!FinalizedOperations::<T>::contains_key(expired_at));
+        assert!(!RootImports::<T>::contains_key(expired_at));
+    }
+
+    /// Commit to `f` operations finalized in the block
+    #[benchmark]
+    fn on_finalize(f: Linear<0, { T::MaxActiveOperations::get() }>) {
+        let now = frame_system::Pallet::<T>::block_number();
+        let initiator: T::AccountId = account("initiator", 0, SEED);
+        let finalized = (0..f)
+            .map(|index| insert_operation::<T>(index, initiator.clone(), JamOperationStatus::Completed, 0))
+            .collect::<Vec<_>>();
+        FinalizedOperations::<T>::insert(now, BoundedVec::try_from(finalized).expect("count is bounded"));
+
+        #[block]
+        {
+            QValidatorJam::<T>::on_finalize(now);
+        }
+
+        assert_eq!(JamStateRoots::<T>::contains_key(now), f > 0);
+    }
+
+    impl_benchmark_test_suite!(QValidatorJam, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,693 @@
+//! QValidator JAM pallet for Matrix-Magiq Immortality Chain
+//!
+//! This pallet keeps JAM (Justified Atomic Merkleization) operations in
+//! bounded on-chain storage. Operations are submitted and justified with
+//! Merkle proofs through extrinsics, and are verified, executed and expired
+//! from `on_initialize`. Every status change emits an event.
+//!
+//! The pallet is the on-chain form of `JamCoordinator`. It shares the
+//! coordinator's operation types, status lifecycle, executor results and
+//! proof verification, but keeps operations in bounded storage instead of
+//! the coordinator's in-memory maps, which a runtime cannot hold. Executors
+//! are fixed by `Config::Executor` rather than registered at run time; a
+//! tuple of executors routes each operation type to the executor handling
+//! it, like the coordinator's per-type registry.
+//!
+//! At the end of each block the pallet commits to the operations finalized
+//! in it with a JAM state root, stored on chain and deposited in the block
+//! digest, so light clients can verify JAM results against block headers.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
+    use jam_quantum_bridge::{
+        executor::JamExecution, generate_jam_proof, verify_justification, JamError, JamOperationStatus,
+        JamOperationType, JamProof, MerkleTree,
+    };
+    use sp_runtime::{
+        traits::{Hash, Saturating},
+        ConsensusEngineId, DigestItem,
+    };
+    use sp_std::vec::Vec;
+
+    use crate::WeightInfo;
+
+    /// Engine ID of the JAM state root digest item
+    pub const JAM_ENGINE_ID: ConsensusEngineId = *b"JAMR";
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        /// The overarching event type.
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Validation and execution handlers for JAM operations, usually a
+        /// tuple with one executor per operation type
+        type Executor: JamOperationExecutor<Self>;
+
+        /// Origin allowed to import trusted Merkle roots and report results
+        /// of dispatched operations
+        type RelayerOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Maximum size of operation data
+        #[pallet::constant]
+        type MaxOperationData: Get<u32>;
+
+        /// Maximum size of the justified data of a proof
+        #[pallet::constant]
+        type MaxJustifiedData: Get<u32>;
+
+        /// Maximum length of a Merkle proof path
+        #[pallet::constant]
+        type MaxProofPathLength: Get<u32>;
+
+        /// Maximum number of proofs per operation
+        #[pallet::constant]
+        type MaxProofs: Get<u32>;
+
+        /// Maximum number of pending and in-progress operations
+        #[pallet::constant]
+        type MaxActiveOperations: Get<u32>;
+
+        /// Number of blocks finalized operations and imported roots are
+        /// kept before pruning
+        ///
+        /// At most `BlockHashCount`, so state proofs can name the hash of
+        /// every retained block.
+        #[pallet::constant]
+        type RetentionPeriod: Get<BlockNumberFor<Self>>;
+
+        /// Maximum number of Merkle roots imported per block
+        #[pallet::constant]
+        type MaxRootImports: Get<u32>;
+
+        /// Weight information for extrinsics and hooks in this pallet.
+        type WeightInfo: WeightInfo;
+    }
+
+    /// Storage for JAM operations by operation hash
+    #[pallet::storage]
+    pub type Operations<T: Config> = StorageMap<
+        _,
+        Identity,
+        T::Hash,
+        JamOperationInfo<T>,
+    >;
+
+    /// Hashes of pending and in-progress operations
+    #[pallet::storage]
+    pub type ActiveOperations<T: Config> = StorageValue<
+        _,
+        BoundedVec<T::Hash, T::MaxActiveOperations>,
+        ValueQuery,
+    >;
+
+    /// Hashes of operations finalized at each block, pruned after the
+    /// retention period
+    #[pallet::storage]
+    pub type FinalizedOperations<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        BoundedVec<T::Hash, T::MaxActiveOperations>,
+        ValueQuery,
+    >;
+
+    /// JAM state root over the operations finalized at each block, pruned
+    /// after the retention period
+    #[pallet::storage]
+    pub type JamStateRoots<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        T::Hash,
+    >;
+
+    /// Trusted Merkle roots and the block they were last imported in, by
+    /// the hash of the block committing them, pruned after the retention
+    /// period
+    #[pallet::storage]
+    pub type TrustedRoots<T: Config> = StorageMap<
+        _,
+        Identity,
+        T::Hash,
+        (T::Hash, BlockNumberFor<T>),
+    >;
+
+    /// Hashes of the blocks whose Merkle roots were imported at each block
+    #[pallet::storage]
+    pub type RootImports<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        BoundedVec<T::Hash, T::MaxRootImports>,
+        ValueQuery,
+    >;
+
+    /// Bounded JAM proof as stored on chain
+    #[derive(Encode, Decode, CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct BoundedJamProof<T: Config> {
+        /// Block hash that contains the Merkle root
+        pub block_hash: T::Hash,
+        /// Merkle root hash
+        pub merkle_root: T::Hash,
+        /// Merkle proof path
+        pub proof_path: BoundedVec<T::Hash, T::MaxProofPathLength>,
+        /// Leaf index
+        pub leaf_index: u64,
+        /// Justified data
+        pub justified_data: BoundedVec<u8, T::MaxJustifiedData>,
+    }
+
+    impl<T: Config> From<BoundedJamProof<T>> for JamProof<T::Hash, T::Hash> {
+        fn from(proof: BoundedJamProof<T>) -> Self {
+            JamProof {
+                block_hash: proof.block_hash,
+                merkle_root: proof.merkle_root,
+                proof_path: proof.proof_path.into_inner(),
+                leaf_index: proof.leaf_index,
+                justified_data: proof.justified_data.into_inner(),
+            }
+        }
+    }
+
+    /// Leaf of the JAM state tree, committing to a finalized operation and
+    /// its final status
+    pub type JamStateLeaf<Hash> = (Hash, JamOperationStatus);
+
+    /// JAM operation as stored on chain
+    #[derive(Encode, Decode, CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    #[codec(mel_bound())]
+    pub struct JamOperationInfo<T: Config> {
+        /// Initiator of the operation
+        pub initiator: T::AccountId,
+        /// Target parachain ID
+        pub target_parachain_id: u32,
+        /// Operation type
+        pub operation_type: JamOperationType,
+        /// Operation data
+        pub data: BoundedVec<u8, T::MaxOperationData>,
+        /// Creation block number
+        pub created_at: BlockNumberFor<T>,
+        /// Expiration block number
+        pub expires_at: BlockNumberFor<T>,
+        /// Associated proofs
+        pub proofs: BoundedVec<BoundedJamProof<T>, T::MaxProofs>,
+        /// Whether the initiator sealed the proofs, making the operation
+        /// ready to execute
+        pub sealed: bool,
+        /// Operation status
+        pub status: JamOperationStatus,
+    }
+
+    /// Validation and execution handler for JAM operation types
+    pub trait JamOperationExecutor<T: Config> {
+        /// Whether the executor handles operations of `operation_type`
+        fn handles(operation_type: JamOperationType) -> bool;
+
+        /// Check an operation before it is accepted
+        fn validate(_operation: &JamOperationInfo<T>) -> Result<(), JamError> {
+            Ok(())
+        }
+
+        /// Execute an operation whose proofs have been verified
+        fn execute(operation_hash: &T::Hash, operation: &JamOperationInfo<T>) -> Result<JamExecution, JamError>;
+    }
+
+    /// Routes each operation to the first executor handling its type
+    #[impl_trait_for_tuples::impl_for_tuples(16)]
+    impl<T: Config> JamOperationExecutor<T> for Tuple {
+        fn handles(operation_type: JamOperationType) -> bool {
+            for_tuples!( #( if <Tuple as JamOperationExecutor<T>>::handles(operation_type) { return true; } )* );
+            false
+        }
+
+        fn validate(operation: &JamOperationInfo<T>) -> Result<(), JamError> {
+            for_tuples!( #(
+                if <Tuple as JamOperationExecutor<T>>::handles(operation.operation_type) {
+                    return <Tuple as JamOperationExecutor<T>>::validate(operation);
+                }
+            )* );
+            Err(JamError::UnsupportedOperationType)
+        }
+
+        fn execute(operation_hash: &T::Hash, operation: &JamOperationInfo<T>) -> Result<JamExecution, JamError> {
+            for_tuples!( #(
+                if <Tuple as JamOperationExecutor<T>>::handles(operation.operation_type) {
+                    return <Tuple as JamOperationExecutor<T>>::execute(operation_hash, operation);
+                }
+            )* );
+            Err(JamError::UnsupportedOperationType)
+        }
+    }
+
+    /// Dispatches every operation of type `OperationType` and waits for the
+    /// relayer to report its result
+    pub struct Relayed<OperationType>(PhantomData<OperationType>);
+
+    impl<T: Config, OperationType: Get<JamOperationType>> JamOperationExecutor<T> for Relayed<OperationType> {
+        fn handles(operation_type: JamOperationType) -> bool {
+            operation_type == OperationType::get()
+        }
+
+        fn execute(_operation_hash: &T::Hash, _operation: &JamOperationInfo<T>) -> Result<JamExecution, JamError> {
+            Ok(JamExecution::Dispatched)
+        }
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// A new JAM operation was submitted
+        OperationSubmitted {
+            operation_hash: T::Hash,
+            initiator: T::AccountId,
+        },
+
+        /// A proof was attached to a JAM operation
+        ProofSubmitted {
+            operation_hash: T::Hash,
+            leaf_index: u64,
+        },
+
+        /// The proofs of a JAM operation were sealed
+        OperationSealed {
+            operation_hash: T::Hash,
+        },
+
+        /// A JAM operation changed status
+        OperationStatusChanged {
+            operation_hash: T::Hash,
+            status: JamOperationStatus,
+        },
+
+        /// A Merkle root was imported as trusted
+        MerkleRootImported {
+            block_hash: T::Hash,
+            merkle_root: T::Hash,
+        },
+
+        /// The JAM state root of a block was committed
+        JamStateRootCommitted {
+            block_number: BlockNumberFor<T>,
+            state_root: T::Hash,
+        },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// Operation already exists
+        OperationAlreadyExists,
+
+        /// Operation not found
+        OperationNotFound,
+
+        /// Operation expired
+        OperationExpired,
+
+        /// Too many active operations
+        TooManyOperations,
+
+        /// Too many proofs for the operation
+        TooManyProofs,
+
+        /// Operation data too large
+        DataTooLarge,
+
+        /// Caller is not the operation initiator
+        NotInitiator,
+
+        /// Operation is not pending
+        NotPending,
+
+        /// Status change not allowed by the operation lifecycle
+        InvalidTransition,
+
+        /// Operation rejected by its executor
+        OperationRejected,
+
+        /// No executor handles the operation type
+        UnsupportedOperationType,
+
+        /// Too many operations finalized in this block
+        TooManyFinalized,
+
+        /// Operation proofs are already sealed
+        AlreadySealed,
+
+        /// Operation has no proofs to seal
+        NoProofs,
+
+        /// Too many Merkle roots imported in this block
+        TooManyRootImports,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+            let active = ActiveOperations::<T>::get();
+            let active_count = active.len() as u32;
+
+            let mut still_active = BoundedVec::<T::Hash, T::MaxActiveOperations>::default();
+            for hash in active {
+                let Some(mut operation) = Operations::<T>::get(hash) else { continue };
+
+                let changes = Self::advance(&hash, &operation, now);
+                if !changes.is_empty() {
+                    for status in changes {
+                        operation.status = status;
+                        Self::deposit_event(Event::OperationStatusChanged { operation_hash: hash, status });
+                    }
+                    Operations::<T>::insert(hash, &operation);
+                }
+
+                if operation.status.is_final() {
+                    // Cannot overflow: the hook runs before any extrinsic
+                    // finalizes operations in this block
+                    let _ = Self::note_finalized(hash, now);
+                } else {
+                    // Cannot overflow: `still_active` is a subset of `active`
+                    let _ = still_active.try_push(hash);
+                }
+            }
+            ActiveOperations::<T>::put(still_active);
+
+            let expired_at = now.saturating_sub(T::RetentionPeriod::get());
+            let mut pruned_count = 0;
+            let mut roots_count = 0;
+            if expired_at < now {
+                let pruned = FinalizedOperations::<T>::take(expired_at);
+                JamStateRoots::<T>::remove(expired_at);
+                pruned_count = pruned.len() as u32;
+                for hash in pruned {
+                    Operations::<T>::remove(hash);
+                }
+
+                let imported = RootImports::<T>::take(expired_at);
+                roots_count = imported.len() as u32;
+                for block_hash in imported {
+                    // Roots imported again later are kept until then
+                    if TrustedRoots::<T>::get(block_hash).map_or(false, |(_, imported_at)| imported_at == expired_at) {
+                        TrustedRoots::<T>::remove(block_hash);
+                    }
+                }
+            }
+
+            // `on_finalize` commits to at most `MaxActiveOperations`
+            // finalized operations
+            T::WeightInfo::on_initialize(active_count, pruned_count, roots_count)
+                .saturating_add(T::WeightInfo::on_finalize(T::MaxActiveOperations::get()))
+        }
+
+        fn on_finalize(now: BlockNumberFor<T>) {
+            let leaves = Self::jam_state_leaves(now);
+            if leaves.is_empty() {
+                return;
+            }
+
+            let state_root = MerkleTree::<T::Hashing>::new(&leaves).root();
+            JamStateRoots::<T>::insert(now, state_root);
+            frame_system::Pallet::<T>::deposit_log(DigestItem::Consensus(JAM_ENGINE_ID, state_root.encode()));
+
+            Self::deposit_event(Event::JamStateRootCommitted { block_number: now, state_root });
+        }
+
+        fn integrity_test() {
+            assert!(
+                T::RetentionPeriod::get() <= T::BlockHashCount::get(),
+                "RetentionPeriod must not exceed BlockHashCount"
+            );
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Submit a new JAM operation
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::WeightInfo::submit_operation(data.len() as u32))]
+        pub fn submit_operation(
+            origin: OriginFor<T>,
+            target_parachain_id: u32,
+            operation_type: JamOperationType,
+            data: Vec<u8>,
+            expires_at: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            let initiator = ensure_signed(origin)?;
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(expires_at > now, Error::<T>::OperationExpired);
+            ensure!(T::Executor::handles(operation_type), Error::<T>::UnsupportedOperationType);
+
+            let operation = JamOperationInfo::<T> {
+                initiator: initiator.clone(),
+                target_parachain_id,
+                operation_type,
+                data: data.try_into().map_err(|_| Error::<T>::DataTooLarge)?,
+                created_at: now,
+                expires_at,
+                proofs: Default::default(),
+                sealed: false,
+                status: JamOperationStatus::Pending,
+            };
+            T::Executor::validate(&operation).map_err(|_| Error::<T>::OperationRejected)?;
+
+            let operation_hash = T::Hashing::hash_of(&operation);
+            ensure!(!Operations::<T>::contains_key(operation_hash), Error::<T>::OperationAlreadyExists);
+            ActiveOperations::<T>::try_mutate(|active| active.try_push(operation_hash))
+                .map_err(|_| Error::<T>::TooManyOperations)?;
+            Operations::<T>::insert(operation_hash, operation);
+
+            Self::deposit_event(Event::OperationSubmitted { operation_hash, initiator });
+            Ok(())
+        }
+
+        /// Attach a Merkle proof to a pending operation that is not sealed
+        ///
+        /// All proofs are verified against the trusted roots before the
+        /// operation is executed, and must justify the operation data.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::submit_proof(proof.proof_path.len() as u32, proof.justified_data.len() as u32))]
+        pub fn submit_proof(
+            origin: OriginFor<T>,
+            operation_hash: T::Hash,
+            proof: BoundedJamProof<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Operations::<T>::try_mutate(operation_hash, |maybe_operation| {
+                let operation = maybe_operation.as_mut().ok_or(Error::<T>::OperationNotFound)?;
+                ensure!(operation.initiator == who, Error::<T>::NotInitiator);
+                ensure!(operation.status == JamOperationStatus::Pending, Error::<T>::NotPending);
+                ensure!(!operation.sealed, Error::<T>::AlreadySealed);
+
+                let leaf_index = proof.leaf_index;
+                operation.proofs.try_push(proof).map_err(|_| Error::<T>::TooManyProofs)?;
+
+                Self::deposit_event(Event::ProofSubmitted { operation_hash, leaf_index });
+                Ok(())
+            })
+        }
+
+        /// Cancel a pending operation, marking it as failed
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::cancel_operation())]
+        pub fn cancel_operation(origin: OriginFor<T>, operation_hash: T::Hash) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let operation = Operations::<T>::get(operation_hash).ok_or(Error::<T>::OperationNotFound)?;
+            ensure!(operation.initiator == who, Error::<T>::NotInitiator);
+            ensure!(operation.status == JamOperationStatus::Pending, Error::<T>::NotPending);
+
+            Self::finalize(operation_hash, JamOperationStatus::Failed)
+        }
+
+        /// Import a Merkle root committed in a block as trusted
+        ///
+        /// The root is trusted for `RetentionPeriod` blocks from its latest
+        /// import.
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::import_merkle_root())]
+        pub fn import_merkle_root(
+            origin: OriginFor<T>,
+            block_hash: T::Hash,
+            merkle_root: T::Hash,
+        ) -> DispatchResult {
+            T::RelayerOrigin::ensure_origin(origin)?;
+
+            let now = frame_system::Pallet::<T>::block_number();
+            let previous = TrustedRoots::<T>::get(block_hash).map(|(_, imported_at)| imported_at);
+            if previous != Some(now) {
+                RootImports::<T>::try_mutate(now, |imports| imports.try_push(block_hash))
+                    .map_err(|_| Error::<T>::TooManyRootImports)?;
+            }
+            TrustedRoots::<T>::insert(block_hash, (merkle_root, now));
+
+            Self::deposit_event(Event::MerkleRootImported { block_hash, merkle_root });
+            Ok(())
+        }
+
+        /// Report the result of a dispatched operation
+        ///
+        /// A result reported at or after the expiry block expires the
+        /// operation instead.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::report_result())]
+        pub fn report_result(origin: OriginFor<T>, operation_hash: T::Hash, success: bool) -> DispatchResult {
+            T::RelayerOrigin::ensure_origin(origin)?;
+
+            let operation = Operations::<T>::get(operation_hash).ok_or(Error::<T>::OperationNotFound)?;
+            ensure!(
+                operation.status == JamOperationStatus::InProgress,
+                Error::<T>::InvalidTransition
+            );
+
+            let now = frame_system::Pallet::<T>::block_number();
+            let status = if operation.expires_at <= now {
+                JamOperationStatus::Expired
+            } else if success {
+                JamOperationStatus::Completed
+            } else {
+                JamOperationStatus::Failed
+            };
+
+            Self::finalize(operation_hash, status)
+        }
+
+        /// Seal the proofs of a pending operation
+        ///
+        /// Operations are only verified and executed once sealed, so proofs
+        /// may be submitted over several blocks.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::seal_operation())]
+        pub fn seal_operation(origin: OriginFor<T>, operation_hash: T::Hash) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Operations::<T>::try_mutate(operation_hash, |maybe_operation| {
+                let operation = maybe_operation.as_mut().ok_or(Error::<T>::OperationNotFound)?;
+                ensure!(operation.initiator == who, Error::<T>::NotInitiator);
+                ensure!(operation.status == JamOperationStatus::Pending, Error::<T>::NotPending);
+                ensure!(!operation.sealed, Error::<T>::AlreadySealed);
+                ensure!(!operation.proofs.is_empty(), Error::<T>::NoProofs);
+
+                operation.sealed = true;
+
+                Self::deposit_event(Event::OperationSealed { operation_hash });
+                Ok(())
+            })
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Status changes of an active operation at block `now`
+        fn advance(
+            hash: &T::Hash,
+            operation: &JamOperationInfo<T>,
+            now: BlockNumberFor<T>,
+        ) -> Vec<JamOperationStatus> {
+            if operation.expires_at <= now {
+                return sp_std::vec![JamOperationStatus::Expired];
+            }
+            if operation.status != JamOperationStatus::Pending || !operation.sealed {
+                return Vec::new();
+            }
+            if !Self::verify_proofs(operation) {
+                return sp_std::vec![JamOperationStatus::Failed];
+            }
+
+            match T::Executor::execute(hash, operation) {
+                Ok(JamExecution::Completed) => {
+                    sp_std::vec![JamOperationStatus::InProgress, JamOperationStatus::Completed]
+                }
+                Ok(JamExecution::Dispatched) => sp_std::vec![JamOperationStatus::InProgress],
+                Err(_) => sp_std::vec![JamOperationStatus::InProgress, JamOperationStatus::Failed],
+            }
+        }
+
+        /// Verify that the proofs of an operation justify its data against
+        /// the trusted roots
+        fn verify_proofs(operation: &JamOperationInfo<T>) -> bool {
+            let proofs: Vec<JamProof<T::Hash, T::Hash>> = operation.proofs.iter().cloned().map(Into::into).collect();
+            verify_justification::<T::Hashing>(&operation.data, &proofs, |block_hash| {
+                TrustedRoots::<T>::get(block_hash).map(|(root, _)| root)
+            })
+        }
+
+        /// Move an active operation to a final status
+        fn finalize(operation_hash: T::Hash, status: JamOperationStatus) -> DispatchResult {
+            Operations::<T>::try_mutate(operation_hash, |maybe_operation| -> DispatchResult {
+                let operation = maybe_operation.as_mut().ok_or(Error::<T>::OperationNotFound)?;
+                ensure!(operation.status.can_transition_to(status), Error::<T>::InvalidTransition);
+                operation.status = status;
+                Ok(())
+            })?;
+
+            Self::note_finalized(operation_hash, frame_system::Pallet::<T>::block_number())?;
+            ActiveOperations::<T>::mutate(|active| active.retain(|hash| *hash != operation_hash));
+
+            Self::deposit_event(Event::OperationStatusChanged { operation_hash, status });
+            Ok(())
+        }
+
+        /// Record an operation as finalized in block `now`, committing it to
+        /// that block's JAM state root and scheduling it for pruning
+        fn note_finalized(operation_hash: T::Hash, now: BlockNumberFor<T>) -> DispatchResult {
+            FinalizedOperations::<T>::try_mutate(now, |finalized| {
+                finalized.try_push(operation_hash).map_err(|_| Error::<T>::TooManyFinalized.into())
+            })
+        }
+
+        /// Leaves of the JAM state tree of a block, in finalization order
+        fn jam_state_leaves(block_number: BlockNumberFor<T>) -> Vec<JamStateLeaf<T::Hash>> {
+            FinalizedOperations::<T>::get(block_number)
+                .into_iter()
+                .filter_map(|hash| Operations::<T>::get(hash).map(|operation| (hash, operation.status)))
+                .collect()
+        }
+
+        /// JAM state root committed at `block_number`
+        pub fn jam_state_root(block_number: BlockNumberFor<T>) -> Option<T::Hash> {
+            JamStateRoots::<T>::get(block_number)
+        }
+
+        /// Inclusion proof of an operation in the JAM state root of the block
+        /// that finalized it
+        ///
+        /// The proof's `block_hash` is the hash of `block_number` and its
+        /// justified data is the encoded `JamStateLeaf`. Only available for
+        /// past blocks within the retention period, whose hashes
+        /// `frame_system` still keeps.
+        pub fn jam_state_proof(
+            block_number: BlockNumberFor<T>,
+            operation_hash: T::Hash,
+        ) -> Option<JamProof<T::Hash, T::Hash>> {
+            let state_root = JamStateRoots::<T>::get(block_number)?;
+            let leaves = Self::jam_state_leaves(block_number);
+            let leaf = leaves.iter().find(|(hash, _)| *hash == operation_hash)?;
+            let block_hash = frame_system::Pallet::<T>::block_hash(block_number);
+
+            let proof = generate_jam_proof::<_, T::Hashing>(leaf, &leaves, block_hash)?;
+            (proof.merkle_root == state_root).then_some(proof)
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use crate as qvalidator_jam;
+use crate::{JamOperationExecutor, JamOperationInfo, Relayed};
+use frame_support::{
+    parameter_types,
+    traits::{ConstU32, ConstU64, Everything},
+};
+use frame_system::EnsureRoot;
+use jam_quantum_bridge::{executor::JamExecution, JamError, JamOperationType};
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, IdentityLookup},
+    BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        QValidatorJam: qvalidator_jam,
+    }
+);
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Nonce = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Block = Block;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+/// Completes asset transfers synchronously, rejecting empty transfers
+pub struct TransferExecutor;
+
+impl JamOperationExecutor<Test> for TransferExecutor {
+    fn handles(operation_type: JamOperationType) -> bool {
+        operation_type == JamOperationType::AssetTransfer
+    }
+
+    fn validate(operation: &JamOperationInfo<Test>) -> Result<(), JamError> {
+        if operation.data.is_empty() {
+            return Err(JamError::Custom(0));
+        }
+        Ok(())
+    }
+
+    fn execute(_operation_hash: &H256, _operation: &JamOperationInfo<Test>) -> Result<JamExecution, JamError> {
+        Ok(JamExecution::Completed)
+    }
+}
+
+/// Fails every `Custom(1)` operation
+pub struct FailingExecutor;
+
+impl JamOperationExecutor<Test> for FailingExecutor {
+    fn handles(operation_type: JamOperationType) -> bool {
+        operation_type == JamOperationType::Custom(1)
+    }
+
+    fn execute(_operation_hash: &H256, _operation: &JamOperationInfo<Test>) -> Result<JamExecution, JamError> {
+        Err(JamError::Custom(1))
+    }
+}
+
+parameter_types! {
+    pub const RetentionPeriod: u64 = 5;
+    pub const MessagePassing: JamOperationType = JamOperationType::MessagePassing;
+}
+
+impl qvalidator_jam::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Executor = (TransferExecutor, FailingExecutor, Relayed<MessagePassing>);
+    type RelayerOrigin = EnsureRoot<u64>;
+    type MaxOperationData = ConstU32<64>;
+    type MaxJustifiedData = ConstU32<64>;
+    type MaxProofPathLength = ConstU32<16>;
+    type MaxProofs = ConstU32<2>;
+    type MaxActiveOperations = ConstU32<3>;
+    type RetentionPeriod = RetentionPeriod;
+    type MaxRootImports = ConstU32<4>;
+    type WeightInfo = ();
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let storage = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
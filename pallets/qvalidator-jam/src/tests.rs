@@ -0,0 +1,402 @@
+use crate::{
+    mock::*, ActiveOperations, BoundedJamProof, Error, Event, JamStateRoots, Operations, RootImports, TrustedRoots,
+    JAM_ENGINE_ID,
+};
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
+use jam_quantum_bridge::{generate_jam_proof, verify_jam_proof, JamOperationStatus, JamOperationType};
+use sp_core::H256;
+use sp_runtime::{traits::BlakeTwo256, DigestItem};
+
+fn run_to_block(n: u64) {
+    while System::block_number() < n {
+        let current = System::block_number();
+        QValidatorJam::on_finalize(current);
+        System::set_block_number(current + 1);
+        QValidatorJam::on_initialize(current + 1);
+    }
+}
+
+fn submit_unsealed(who: u64, operation_type: JamOperationType, data: u8, expires_at: u64) -> H256 {
+    assert_ok!(QValidatorJam::submit_operation(
+        RuntimeOrigin::signed(who),
+        2000,
+        operation_type,
+        vec![data],
+        expires_at,
+    ));
+    *ActiveOperations::<Test>::get().last().unwrap()
+}
+
+/// Submit an operation carrying one trusted proof of its data and seal it
+fn submit(who: u64, operation_type: JamOperationType, data: u8, expires_at: u64) -> H256 {
+    let hash = submit_unsealed(who, operation_type, data, expires_at);
+    let proof = proof_for(data, &ITEMS);
+    assert_ok!(QValidatorJam::import_merkle_root(RuntimeOrigin::root(), proof.block_hash, proof.merkle_root));
+    assert_ok!(QValidatorJam::submit_proof(RuntimeOrigin::signed(who), hash, proof));
+    assert_ok!(QValidatorJam::seal_operation(RuntimeOrigin::signed(who), hash));
+    hash
+}
+
+fn status(hash: H256) -> JamOperationStatus {
+    Operations::<Test>::get(hash).unwrap().status
+}
+
+fn status_event(operation_hash: H256, status: JamOperationStatus) -> RuntimeEvent {
+    Event::OperationStatusChanged { operation_hash, status }.into()
+}
+
+/// Items committed in the trusted test block, one per operation data byte
+const ITEMS: [u8; 5] = [0, 1, 2, 3, 4];
+
+/// Proof that `item` is committed among `items`, justifying operations
+/// whose data is `[item]`
+fn proof_for(item: u8, items: &[u8]) -> BoundedJamProof<Test> {
+    let proof = generate_jam_proof::<_, BlakeTwo256>(&item, items, H256::repeat_byte(0xbb)).unwrap();
+    BoundedJamProof {
+        block_hash: proof.block_hash,
+        merkle_root: proof.merkle_root,
+        proof_path: proof.proof_path.try_into().unwrap(),
+        leaf_index: proof.leaf_index,
+        justified_data: proof.justified_data.try_into().unwrap(),
+    }
+}
+
+#[test]
+fn submit_operation_works() {
+    new_test_ext().execute_with(|| {
+        let hash = submit_unsealed(1, JamOperationType::MessagePassing, 0, 10);
+
+        let operation = Operations::<Test>::get(hash).unwrap();
+        assert_eq!(operation.initiator, 1);
+        assert_eq!(operation.status, JamOperationStatus::Pending);
+        assert!(!operation.sealed);
+        assert_eq!(operation.created_at, 1);
+        System::assert_last_event(Event::OperationSubmitted { operation_hash: hash, initiator: 1 }.into());
+    });
+}
+
+#[test]
+fn submit_operation_rejections() {
+    new_test_ext().execute_with(|| {
+        let origin = || RuntimeOrigin::signed(1);
+        let transfer = JamOperationType::AssetTransfer;
+
+        assert_noop!(
+            QValidatorJam::submit_operation(origin(), 2000, transfer, vec![0], 1),
+            Error::<Test>::OperationExpired
+        );
+        assert_noop!(
+            QValidatorJam::submit_operation(origin(), 2000, transfer, vec![0; 65], 10),
+            Error::<Test>::DataTooLarge
+        );
+        assert_noop!(
+            QValidatorJam::submit_operation(origin(), 2000, transfer, vec![], 10),
+            Error::<Test>::OperationRejected
+        );
+        assert_noop!(
+            QValidatorJam::submit_operation(origin(), 2000, JamOperationType::Custom(9), vec![0], 10),
+            Error::<Test>::UnsupportedOperationType
+        );
+
+        submit(1, transfer, 0, 10);
+        assert_noop!(
+            QValidatorJam::submit_operation(origin(), 2000, transfer, vec![0], 10),
+            Error::<Test>::OperationAlreadyExists
+        );
+
+        submit(1, transfer, 1, 10);
+        submit(1, transfer, 2, 10);
+        assert_noop!(
+            QValidatorJam::submit_operation(origin(), 2000, transfer, vec![3], 10),
+            Error::<Test>::TooManyOperations
+        );
+    });
+}
+
+#[test]
+fn on_initialize_executes_by_type() {
+    new_test_ext().execute_with(|| {
+        let completed = submit(1, JamOperationType::AssetTransfer, 0, 10);
+        let dispatched = submit(1, JamOperationType::MessagePassing, 0, 10);
+        let failed = submit(1, JamOperationType::Custom(1), 0, 10);
+
+        run_to_block(2);
+        assert_eq!(status(completed), JamOperationStatus::Completed);
+        assert_eq!(status(dispatched), JamOperationStatus::InProgress);
+        assert_eq!(status(failed), JamOperationStatus::Failed);
+        assert_eq!(ActiveOperations::<Test>::get().into_inner(), vec![dispatched]);
+
+        let events = System::events().into_iter().map(|record| record.event).collect::<Vec<_>>();
+        assert!(events.contains(&status_event(completed, JamOperationStatus::InProgress)));
+        assert!(events.contains(&status_event(completed, JamOperationStatus::Completed)));
+        assert!(events.contains(&status_event(failed, JamOperationStatus::Failed)));
+
+        assert_noop!(
+            QValidatorJam::report_result(RuntimeOrigin::signed(1), dispatched, true),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(QValidatorJam::report_result(RuntimeOrigin::root(), dispatched, true));
+        assert_eq!(status(dispatched), JamOperationStatus::Completed);
+        assert!(ActiveOperations::<Test>::get().is_empty());
+        assert_noop!(
+            QValidatorJam::report_result(RuntimeOrigin::root(), dispatched, true),
+            Error::<Test>::InvalidTransition
+        );
+    });
+}
+
+#[test]
+fn proofs_are_verified_against_trusted_roots() {
+    new_test_ext().execute_with(|| {
+        let valid = proof_for(0, &ITEMS);
+        let mut tampered = proof_for(1, &ITEMS);
+        tampered.proof_path[0].as_mut()[0] ^= 0x01;
+
+        let justified = submit_unsealed(1, JamOperationType::AssetTransfer, 0, 10);
+        let forged = submit_unsealed(1, JamOperationType::AssetTransfer, 1, 10);
+        let untrusted = submit_unsealed(2, JamOperationType::AssetTransfer, 2, 10);
+
+        assert_noop!(
+            QValidatorJam::submit_proof(RuntimeOrigin::signed(2), justified, valid.clone()),
+            Error::<Test>::NotInitiator
+        );
+        assert_ok!(QValidatorJam::submit_proof(RuntimeOrigin::signed(1), justified, valid.clone()));
+        assert_ok!(QValidatorJam::submit_proof(RuntimeOrigin::signed(1), forged, tampered));
+
+        let mut other_block = proof_for(2, &ITEMS);
+        other_block.block_hash = H256::repeat_byte(0xcc);
+        assert_ok!(QValidatorJam::submit_proof(RuntimeOrigin::signed(2), untrusted, other_block));
+
+        assert_ok!(QValidatorJam::import_merkle_root(RuntimeOrigin::root(), valid.block_hash, valid.merkle_root));
+        assert_ok!(QValidatorJam::seal_operation(RuntimeOrigin::signed(1), justified));
+        assert_ok!(QValidatorJam::seal_operation(RuntimeOrigin::signed(1), forged));
+        assert_ok!(QValidatorJam::seal_operation(RuntimeOrigin::signed(2), untrusted));
+        run_to_block(2);
+
+        assert_eq!(status(justified), JamOperationStatus::Completed);
+        assert_eq!(status(forged), JamOperationStatus::Failed);
+        assert_eq!(status(untrusted), JamOperationStatus::Failed);
+        assert_noop!(
+            QValidatorJam::submit_proof(RuntimeOrigin::signed(1), justified, valid),
+            Error::<Test>::NotPending
+        );
+    });
+}
+
+#[test]
+fn proofs_must_justify_the_operation_data() {
+    new_test_ext().execute_with(|| {
+        let proof = proof_for(0, &ITEMS);
+        assert_ok!(QValidatorJam::import_merkle_root(RuntimeOrigin::root(), proof.block_hash, proof.merkle_root));
+        let justified = submit_unsealed(1, JamOperationType::AssetTransfer, 0, 10);
+        let unrelated = submit_unsealed(1, JamOperationType::AssetTransfer, 1, 10);
+
+        for hash in [justified, unrelated] {
+            assert_ok!(QValidatorJam::submit_proof(RuntimeOrigin::signed(1), hash, proof.clone()));
+            assert_ok!(QValidatorJam::seal_operation(RuntimeOrigin::signed(1), hash));
+        }
+        run_to_block(2);
+
+        assert_eq!(status(justified), JamOperationStatus::Completed);
+        assert_eq!(status(unrelated), JamOperationStatus::Failed);
+    });
+}
+
+#[test]
+fn proofs_may_arrive_in_later_blocks() {
+    new_test_ext().execute_with(|| {
+        let proof = proof_for(0, &ITEMS);
+        assert_ok!(QValidatorJam::import_merkle_root(RuntimeOrigin::root(), proof.block_hash, proof.merkle_root));
+        let hash = submit_unsealed(1, JamOperationType::AssetTransfer, 0, 10);
+
+        // Not executed without proofs, however many blocks pass
+        run_to_block(3);
+        assert_eq!(status(hash), JamOperationStatus::Pending);
+        assert_noop!(
+            QValidatorJam::seal_operation(RuntimeOrigin::signed(1), hash),
+            Error::<Test>::NoProofs
+        );
+
+        assert_ok!(QValidatorJam::submit_proof(RuntimeOrigin::signed(1), hash, proof.clone()));
+        run_to_block(4);
+        assert_eq!(status(hash), JamOperationStatus::Pending);
+
+        assert_noop!(
+            QValidatorJam::seal_operation(RuntimeOrigin::signed(2), hash),
+            Error::<Test>::NotInitiator
+        );
+        assert_ok!(QValidatorJam::seal_operation(RuntimeOrigin::signed(1), hash));
+        System::assert_last_event(Event::OperationSealed { operation_hash: hash }.into());
+        assert_noop!(
+            QValidatorJam::submit_proof(RuntimeOrigin::signed(1), hash, proof),
+            Error::<Test>::AlreadySealed
+        );
+        assert_noop!(
+            QValidatorJam::seal_operation(RuntimeOrigin::signed(1), hash),
+            Error::<Test>::AlreadySealed
+        );
+
+        run_to_block(5);
+        assert_eq!(status(hash), JamOperationStatus::Completed);
+    });
+}
+
+#[test]
+fn submit_proof_is_bounded() {
+    new_test_ext().execute_with(|| {
+        let proof = proof_for(0, &ITEMS);
+        let hash = submit_unsealed(1, JamOperationType::AssetTransfer, 0, 10);
+
+        assert_ok!(QValidatorJam::submit_proof(RuntimeOrigin::signed(1), hash, proof.clone()));
+        assert_ok!(QValidatorJam::submit_proof(RuntimeOrigin::signed(1), hash, proof.clone()));
+        assert_noop!(
+            QValidatorJam::submit_proof(RuntimeOrigin::signed(1), hash, proof),
+            Error::<Test>::TooManyProofs
+        );
+    });
+}
+
+#[test]
+fn cancel_operation_works() {
+    new_test_ext().execute_with(|| {
+        let hash = submit(1, JamOperationType::MessagePassing, 0, 10);
+
+        assert_noop!(
+            QValidatorJam::cancel_operation(RuntimeOrigin::signed(2), hash),
+            Error::<Test>::NotInitiator
+        );
+        assert_ok!(QValidatorJam::cancel_operation(RuntimeOrigin::signed(1), hash));
+        assert_eq!(status(hash), JamOperationStatus::Failed);
+        assert!(ActiveOperations::<Test>::get().is_empty());
+        System::assert_last_event(status_event(hash, JamOperationStatus::Failed));
+
+        assert_noop!(
+            QValidatorJam::cancel_operation(RuntimeOrigin::signed(1), hash),
+            Error::<Test>::NotPending
+        );
+        assert_noop!(
+            QValidatorJam::cancel_operation(RuntimeOrigin::signed(1), H256::zero()),
+            Error::<Test>::OperationNotFound
+        );
+    });
+}
+
+#[test]
+fn operations_expire() {
+    new_test_ext().execute_with(|| {
+        let dispatched = submit(1, JamOperationType::MessagePassing, 0, 4);
+        run_to_block(2);
+        assert_eq!(status(dispatched), JamOperationStatus::InProgress);
+
+        let pending = submit(1, JamOperationType::MessagePassing, 1, 3);
+        // Reaches its expiry block before any hook could execute it
+        run_to_block(3);
+        assert_eq!(status(pending), JamOperationStatus::Expired);
+
+        run_to_block(4);
+        assert_eq!(status(dispatched), JamOperationStatus::Expired);
+        assert!(ActiveOperations::<Test>::get().is_empty());
+        System::assert_last_event(status_event(dispatched, JamOperationStatus::Expired));
+    });
+}
+
+#[test]
+fn finalized_operations_are_pruned() {
+    new_test_ext().execute_with(|| {
+        let hash = submit(1, JamOperationType::AssetTransfer, 0, 20);
+        run_to_block(2);
+        assert_eq!(status(hash), JamOperationStatus::Completed);
+
+        run_to_block(2 + RetentionPeriod::get() - 1);
+        assert!(Operations::<Test>::contains_key(hash));
+
+        run_to_block(2 + RetentionPeriod::get());
+        assert!(!Operations::<Test>::contains_key(hash));
+    });
+}
+
+#[test]
+fn trusted_roots_are_pruned() {
+    new_test_ext().execute_with(|| {
+        let import = |byte: u8| {
+            QValidatorJam::import_merkle_root(RuntimeOrigin::root(), H256::repeat_byte(byte), H256::repeat_byte(0xff))
+        };
+        for byte in 1..=4 {
+            assert_ok!(import(byte));
+        }
+        assert_noop!(import(5), Error::<Test>::TooManyRootImports);
+        // Importing again in the same block takes no new slot
+        assert_ok!(import(1));
+        assert_eq!(RootImports::<Test>::get(1).len(), 4);
+
+        run_to_block(3);
+        assert_ok!(import(2));
+
+        run_to_block(1 + RetentionPeriod::get());
+        assert!(!TrustedRoots::<Test>::contains_key(H256::repeat_byte(1)));
+        assert!(!RootImports::<Test>::contains_key(1));
+        // Kept from its latest import
+        assert_eq!(TrustedRoots::<Test>::get(H256::repeat_byte(2)), Some((H256::repeat_byte(0xff), 3)));
+
+        run_to_block(3 + RetentionPeriod::get());
+        assert!(!TrustedRoots::<Test>::contains_key(H256::repeat_byte(2)));
+    });
+}
+
+#[test]
+fn too_many_finalized_in_one_block() {
+    new_test_ext().execute_with(|| {
+        for data in 0..3 {
+            let hash = submit(1, JamOperationType::MessagePassing, data, 10);
+            assert_ok!(QValidatorJam::cancel_operation(RuntimeOrigin::signed(1), hash));
+        }
+
+        let hash = submit(1, JamOperationType::MessagePassing, 3, 10);
+        assert_noop!(
+            QValidatorJam::cancel_operation(RuntimeOrigin::signed(1), hash),
+            Error::<Test>::TooManyFinalized
+        );
+
+        run_to_block(2);
+        assert_ok!(QValidatorJam::cancel_operation(RuntimeOrigin::signed(1), hash));
+    });
+}
+
+#[test]
+fn jam_state_root_committed_per_block() {
+    new_test_ext().execute_with(|| {
+        let completed = submit(1, JamOperationType::AssetTransfer, 0, 10);
+        let failed = submit(1, JamOperationType::Custom(1), 0, 10);
+        let dispatched = submit(1, JamOperationType::MessagePassing, 0, 10);
+        run_to_block(2);
+        QValidatorJam::on_finalize(2);
+
+        let state_root = JamStateRoots::<Test>::get(2).unwrap();
+        assert_eq!(QValidatorJam::jam_state_root(2), Some(state_root));
+        assert!(System::digest().logs.contains(&DigestItem::Consensus(JAM_ENGINE_ID, state_root.encode())));
+        System::assert_last_event(Event::JamStateRootCommitted { block_number: 2, state_root }.into());
+
+        let block_hash = H256::repeat_byte(0x22);
+        frame_system::BlockHash::<Test>::insert(2, block_hash);
+        let proof = QValidatorJam::jam_state_proof(2, completed).unwrap();
+        assert_eq!(proof.block_hash, block_hash);
+        assert_eq!(proof.merkle_root, state_root);
+        assert_eq!(proof.justified_data, (completed, JamOperationStatus::Completed).encode());
+        assert!(verify_jam_proof::<BlakeTwo256>(&proof, state_root));
+
+        assert!(QValidatorJam::jam_state_proof(2, failed).is_some());
+        assert!(QValidatorJam::jam_state_proof(2, dispatched).is_none());
+        assert!(QValidatorJam::jam_state_proof(1, completed).is_none());
+    });
+}
+
+#[test]
+fn empty_blocks_commit_no_root() {
+    new_test_ext().execute_with(|| {
+        submit(1, JamOperationType::MessagePassing, 0, 10);
+        run_to_block(3);
+        assert!(JamStateRoots::<Test>::get(2).is_none());
+        assert!(System::digest().logs.is_empty());
+    });
+}
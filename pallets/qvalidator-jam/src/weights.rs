@@ -0,0 +1,155 @@
+//! Weights for qvalidator_jam
+//!
+//! PLACEHOLDER: hand-written estimates, not benchmark results. They have
+//! not been measured on any hardware and must be regenerated before use
+//! in production. Until then `SubstrateWeight` is deprecated, so a runtime
+//! using it gets a warning. Regenerate with:
+//!
+//! ./target/release/imrt-node benchmark pallet --chain=dev --steps=50 --repeat=20
+//!     --pallet=qvalidator_jam --extrinsic='*' --wasm-execution=compiled
+//!     --output=pallets/qvalidator-jam/src/weights.rs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for qvalidator_jam.
+pub trait WeightInfo {
+    fn submit_operation(d: u32, ) -> Weight;
+    fn submit_proof(l: u32, j: u32, ) -> Weight;
+    fn cancel_operation() -> Weight;
+    fn import_merkle_root() -> Weight;
+    fn report_result() -> Weight;
+    fn seal_operation() -> Weight;
+    fn on_initialize(a: u32, p: u32, r: u32, ) -> Weight;
+    fn on_finalize(f: u32, ) -> Weight;
+}
+
+/// Placeholder weights for qvalidator_jam, see the module documentation.
+#[deprecated(note = "placeholder weights; regenerate them from the benchmarks before use")]
+pub struct SubstrateWeight<T>(PhantomData<T>);
+#[allow(deprecated)]
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    fn submit_operation(d: u32, ) -> Weight {
+        Weight::from_parts(34_000_000, 6_500)
+            .saturating_add(Weight::from_parts(1_800, 0).saturating_mul(d.into()))
+            .saturating_add(T::DbWeight::get().reads(2_u64))
+            .saturating_add(T::DbWeight::get().writes(2_u64))
+    }
+    fn submit_proof(l: u32, j: u32, ) -> Weight {
+        Weight::from_parts(28_000_000, 12_000)
+            .saturating_add(Weight::from_parts(95_000, 0).saturating_mul(l.into()))
+            .saturating_add(Weight::from_parts(1_200, 0).saturating_mul(j.into()))
+            .saturating_add(T::DbWeight::get().reads(1_u64))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+    }
+    fn cancel_operation() -> Weight {
+        Weight::from_parts(42_000_000, 12_000)
+            .saturating_add(T::DbWeight::get().reads(3_u64))
+            .saturating_add(T::DbWeight::get().writes(3_u64))
+    }
+    fn import_merkle_root() -> Weight {
+        Weight::from_parts(18_000_000, 3_700)
+            .saturating_add(T::DbWeight::get().reads(2_u64))
+            .saturating_add(T::DbWeight::get().writes(2_u64))
+    }
+    fn report_result() -> Weight {
+        Weight::from_parts(43_000_000, 12_000)
+            .saturating_add(T::DbWeight::get().reads(3_u64))
+            .saturating_add(T::DbWeight::get().writes(3_u64))
+    }
+    fn seal_operation() -> Weight {
+        Weight::from_parts(26_000_000, 12_000)
+            .saturating_add(T::DbWeight::get().reads(1_u64))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+    }
+    fn on_initialize(a: u32, p: u32, r: u32, ) -> Weight {
+        Weight::from_parts(12_000_000, 3_500)
+            .saturating_add(Weight::from_parts(310_000_000, 0).saturating_mul(a.into()))
+            .saturating_add(Weight::from_parts(0, 24_000).saturating_mul(a.into()))
+            .saturating_add(Weight::from_parts(4_800_000, 0).saturating_mul(p.into()))
+            .saturating_add(Weight::from_parts(0, 12_000).saturating_mul(p.into()))
+            .saturating_add(Weight::from_parts(3_500_000, 0).saturating_mul(r.into()))
+            .saturating_add(Weight::from_parts(0, 2_600).saturating_mul(r.into()))
+            .saturating_add(T::DbWeight::get().reads(4_u64))
+            .saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(a.into())))
+            .saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(r.into())))
+            .saturating_add(T::DbWeight::get().writes(4_u64))
+            .saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(a.into())))
+            .saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(p.into())))
+            .saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(r.into())))
+    }
+    fn on_finalize(f: u32, ) -> Weight {
+        Weight::from_parts(6_000_000, 3_500)
+            .saturating_add(Weight::from_parts(9_500_000, 0).saturating_mul(f.into()))
+            .saturating_add(Weight::from_parts(0, 12_000).saturating_mul(f.into()))
+            .saturating_add(T::DbWeight::get().reads(2_u64))
+            .saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(f.into())))
+            .saturating_add(T::DbWeight::get().writes(2_u64))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn submit_operation(d: u32, ) -> Weight {
+        Weight::from_parts(34_000_000, 6_500)
+            .saturating_add(Weight::from_parts(1_800, 0).saturating_mul(d.into()))
+            .saturating_add(RocksDbWeight::get().reads(2_u64))
+            .saturating_add(RocksDbWeight::get().writes(2_u64))
+    }
+    fn submit_proof(l: u32, j: u32, ) -> Weight {
+        Weight::from_parts(28_000_000, 12_000)
+            .saturating_add(Weight::from_parts(95_000, 0).saturating_mul(l.into()))
+            .saturating_add(Weight::from_parts(1_200, 0).saturating_mul(j.into()))
+            .saturating_add(RocksDbWeight::get().reads(1_u64))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+    }
+    fn cancel_operation() -> Weight {
+        Weight::from_parts(42_000_000, 12_000)
+            .saturating_add(RocksDbWeight::get().reads(3_u64))
+            .saturating_add(RocksDbWeight::get().writes(3_u64))
+    }
+    fn import_merkle_root() -> Weight {
+        Weight::from_parts(18_000_000, 3_700)
+            .saturating_add(RocksDbWeight::get().reads(2_u64))
+            .saturating_add(RocksDbWeight::get().writes(2_u64))
+    }
+    fn report_result() -> Weight {
+        Weight::from_parts(43_000_000, 12_000)
+            .saturating_add(RocksDbWeight::get().reads(3_u64))
+            .saturating_add(RocksDbWeight::get().writes(3_u64))
+    }
+    fn seal_operation() -> Weight {
+        Weight::from_parts(26_000_000, 12_000)
+            .saturating_add(RocksDbWeight::get().reads(1_u64))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+    }
+    fn on_initialize(a: u32, p: u32, r: u32, ) -> Weight {
+        Weight::from_parts(12_000_000, 3_500)
+            .saturating_add(Weight::from_parts(310_000_000, 0).saturating_mul(a.into()))
+            .saturating_add(Weight::from_parts(0, 24_000).saturating_mul(a.into()))
+            .saturating_add(Weight::from_parts(4_800_000, 0).saturating_mul(p.into()))
+            .saturating_add(Weight::from_parts(0, 12_000).saturating_mul(p.into()))
+            .saturating_add(Weight::from_parts(3_500_000, 0).saturating_mul(r.into()))
+            .saturating_add(Weight::from_parts(0, 2_600).saturating_mul(r.into()))
+            .saturating_add(RocksDbWeight::get().reads(4_u64))
+            .saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(a.into())))
+            .saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(r.into())))
+            .saturating_add(RocksDbWeight::get().writes(4_u64))
+            .saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(a.into())))
+            .saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(p.into())))
+            .saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(r.into())))
+    }
+    fn on_finalize(f: u32, ) -> Weight {
+        Weight::from_parts(6_000_000, 3_500)
+            .saturating_add(Weight::from_parts(9_500_000, 0).saturating_mul(f.into()))
+            .saturating_add(Weight::from_parts(0, 12_000).saturating_mul(f.into()))
+            .saturating_add(RocksDbWeight::get().reads(2_u64))
+            .saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(f.into())))
+            .saturating_add(RocksDbWeight::get().writes(2_u64))
+    }
+}
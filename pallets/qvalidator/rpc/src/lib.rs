@@ -0,0 +1,175 @@
+//! JSON-RPC interface of the QValidator pallet
+//!
+//! Serves validator and operation state through the `QValidatorApi` runtime
+//! API, and streams operations validated in new best blocks to subscribers.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use futures::{future, stream, FutureExt, StreamExt};
+use jsonrpsee::{
+    core::{Error as JsonRpseeError, RpcResult},
+    proc_macros::rpc,
+    types::{
+        error::{CallError, ErrorObject},
+        SubscriptionResult,
+    },
+    SubscriptionSink,
+};
+use quantum_validator::{AggregateMetrics, ValidationStatus, ValidatorInfo};
+pub use quantum_validator_runtime_api::QValidatorApi as QValidatorRuntimeApi;
+use sc_client_api::BlockchainEvents;
+use sc_rpc::SubscriptionTaskExecutor;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+
+/// Error code of failed runtime API calls
+const RUNTIME_ERROR: i32 = 1;
+
+/// Operation validated in a block, as streamed to subscribers
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationValidated<Hash> {
+    /// Block the operation was finalized in
+    pub block_hash: Hash,
+    /// Operation hash
+    pub operation_hash: Hash,
+    /// Final validation status
+    pub status: ValidationStatus,
+}
+
+/// QValidator RPC methods
+#[rpc(client, server)]
+pub trait QValidatorApi<BlockHash, AccountId, Balance> {
+    /// State of a registered validator
+    #[method(name = "qvalidator_validatorState")]
+    fn validator_state(&self, who: AccountId, at: Option<BlockHash>) -> RpcResult<Option<ValidatorInfo<Balance>>>;
+
+    /// Page of active validators from `start`
+    #[method(name = "qvalidator_activeValidators")]
+    fn active_validators(&self, start: u32, limit: u32, at: Option<BlockHash>) -> RpcResult<Vec<AccountId>>;
+
+    /// Validation status of an operation
+    #[method(name = "qvalidator_operationStatus")]
+    fn operation_status(&self, operation_hash: BlockHash, at: Option<BlockHash>) -> RpcResult<Option<ValidationStatus>>;
+
+    /// Operations still collecting votes that a validator is assigned to
+    #[method(name = "qvalidator_pendingOperations")]
+    fn pending_operations(&self, who: AccountId, at: Option<BlockHash>) -> RpcResult<Vec<BlockHash>>;
+
+    /// Metrics aggregated over all registered validators
+    #[method(name = "qvalidator_aggregateMetrics")]
+    fn aggregate_metrics(&self, at: Option<BlockHash>) -> RpcResult<AggregateMetrics<Balance>>;
+
+    /// Stream operations validated in new best blocks
+    ///
+    /// Operations of blocks that are later retracted are not withdrawn.
+    #[subscription(
+        name = "qvalidator_subscribeOperationValidated" => "qvalidator_operationValidated",
+        unsubscribe = "qvalidator_unsubscribeOperationValidated",
+        item = OperationValidated<BlockHash>
+    )]
+    fn subscribe_operation_validated(&self);
+}
+
+/// QValidator RPC handler
+pub struct QValidator<C, Block> {
+    client: Arc<C>,
+    executor: SubscriptionTaskExecutor,
+    _marker: PhantomData<Block>,
+}
+
+impl<C, Block> QValidator<C, Block> {
+    /// Create a handler serving state of `client`
+    pub fn new(client: Arc<C>, executor: SubscriptionTaskExecutor) -> Self {
+        Self { client, executor, _marker: PhantomData }
+    }
+}
+
+impl<C, Block, AccountId, Balance> QValidatorApiServer<<Block as BlockT>::Hash, AccountId, Balance>
+    for QValidator<C, Block>
+where
+    Block: BlockT,
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockchainEvents<Block> + Send + Sync + 'static,
+    C::Api: QValidatorRuntimeApi<Block, AccountId, Balance, <Block as BlockT>::Hash>,
+    AccountId: Codec + DeserializeOwned + Serialize + Send + Sync + 'static,
+    Balance: Codec + DeserializeOwned + Serialize + Send + Sync + 'static,
+{
+    fn validator_state(
+        &self,
+        who: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Option<ValidatorInfo<Balance>>> {
+        self.client.runtime_api().validator_state(self.at(at), who).map_err(runtime_error)
+    }
+
+    fn active_validators(
+        &self,
+        start: u32,
+        limit: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<AccountId>> {
+        self.client.runtime_api().active_validators(self.at(at), start, limit).map_err(runtime_error)
+    }
+
+    fn operation_status(
+        &self,
+        operation_hash: <Block as BlockT>::Hash,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Option<ValidationStatus>> {
+        self.client.runtime_api().operation_status(self.at(at), operation_hash).map_err(runtime_error)
+    }
+
+    fn pending_operations(
+        &self,
+        who: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<<Block as BlockT>::Hash>> {
+        self.client.runtime_api().pending_operations(self.at(at), who).map_err(runtime_error)
+    }
+
+    fn aggregate_metrics(&self, at: Option<<Block as BlockT>::Hash>) -> RpcResult<AggregateMetrics<Balance>> {
+        self.client.runtime_api().aggregate_metrics(self.at(at)).map_err(runtime_error)
+    }
+
+    fn subscribe_operation_validated(&self, sink: SubscriptionSink) -> SubscriptionResult {
+        let client = self.client.clone();
+        let validated = self
+            .client
+            .import_notification_stream()
+            .filter(|notification| future::ready(notification.is_new_best))
+            .flat_map(move |notification| {
+                let block_hash = notification.hash;
+                let operations = client.runtime_api().operations_validated(block_hash).unwrap_or_default();
+                stream::iter(operations.into_iter().map(move |(operation_hash, status)| OperationValidated {
+                    block_hash,
+                    operation_hash,
+                    status,
+                }))
+            });
+
+        let fut = async move {
+            sink.pipe_from_stream(validated).await;
+        };
+        self.executor.spawn("qvalidator-rpc-subscription", Some("rpc"), fut.boxed());
+        Ok(())
+    }
+}
+
+impl<C, Block> QValidator<C, Block>
+where
+    Block: BlockT,
+    C: HeaderBackend<Block>,
+{
+    /// Requested block, or the best block
+    fn at(&self, at: Option<<Block as BlockT>::Hash>) -> <Block as BlockT>::Hash {
+        at.unwrap_or_else(|| self.client.info().best_hash)
+    }
+}
+
+/// Convert a runtime API error into an RPC error
+fn runtime_error(err: impl std::fmt::Debug) -> JsonRpseeError {
+    CallError::Custom(ErrorObject::owned(RUNTIME_ERROR, "Runtime error", Some(format!("{:?}", err)))).into()
+}
@@ -0,0 +1,41 @@
+//! Runtime API for the QValidator pallet
+//!
+//! Exposes validator and operation state to the node, so RPC clients and
+//! dashboards can query it without decoding storage themselves.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use quantum_validator::{AggregateMetrics, QuantumKeyType, ValidationStatus, ValidatorInfo};
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    /// Validator and operation state of the QValidator pallet
+    pub trait QValidatorApi<AccountId, Balance, Hash>
+    where
+        AccountId: Codec,
+        Balance: Codec,
+        Hash: Codec,
+    {
+        /// State of a registered validator
+        fn validator_state(who: AccountId) -> Option<ValidatorInfo<Balance>>;
+
+        /// Page of active validators from `start`
+        fn active_validators(start: u32, limit: u32) -> Vec<AccountId>;
+
+        /// Validation status of an operation
+        fn operation_status(operation_hash: Hash) -> Option<ValidationStatus>;
+
+        /// Operations still collecting votes that a validator is assigned to
+        fn pending_operations(who: AccountId) -> Vec<Hash>;
+
+        /// Key types, quantum public keys and stake of the active validators
+        fn staked_keys() -> Vec<(AccountId, QuantumKeyType, Vec<u8>, Balance)>;
+
+        /// Metrics aggregated over all registered validators
+        fn aggregate_metrics() -> AggregateMetrics<Balance>;
+
+        /// Operations finalized in the block
+        fn operations_validated() -> Vec<(Hash, ValidationStatus)>;
+    }
+}
@@ -0,0 +1,255 @@
+//! Benchmarks for the QValidator pallet
+
+use super::*;
+use crate::Pallet as QValidator;
+use frame_benchmarking::v2::*;
+use frame_support::{
+    assert_ok,
+    traits::{
+        fungible::{Inspect, Mutate},
+        Get, Hooks,
+    },
+};
+use frame_system::{pallet_prelude::BlockNumberFor, RawOrigin};
+use sp_runtime::traits::{Hash, Saturating};
+use sp_std::{vec, vec::Vec};
+
+const SEED: u32 = 0;
+
+/// Upper bound of operations expired, and so pruned, in one block
+const MAX_PRUNED: u32 = 100;
+
+/// Account funded to stake twice the minimum
+fn funded<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+    let who = account(name, index, SEED);
+    let stake = T::MinValidatorStake::get();
+    T::Currency::set_balance(&who, T::Currency::minimum_balance().saturating_add(stake.saturating_add(stake)));
+    who
+}
+
+/// Register validators staking the minimum, with keys of the maximum size
+fn register_validators<T: Config>(count: u32) -> Vec<T::AccountId> {
+    (0..count)
+        .map(|index| {
+            let who = funded::<T>("validator", index);
+            assert_ok!(QValidator::<T>::register_validator(
+                RawOrigin::Signed(who.clone()).into(),
+                T::MinValidatorStake::get(),
+                QuantumKeyType::ECDSA,
+                vec![0; T::MaxQuantumStateSize::get() as usize],
+            ));
+            who
+        })
+        .collect()
+}
+
+/// Submit an operation with data of the maximum size
+fn submit<T: Config>() -> T::Hash {
+    let submitter: T::AccountId = account("submitter", 0, SEED);
+    let expires_at = frame_system::Pallet::<T>::block_number().saturating_add(10u32.into());
+    assert_ok!(QValidator::<T>::submit_operation(
+        RawOrigin::Signed(submitter).into(),
+        0,
+        OperationType::Measurement,
+        vec![1; T::MaxQuantumStateSize::get() as usize],
+        expires_at,
+    ));
+    PendingOperations::<T>::iter_keys().next().expect("operation was submitted")
+}
+
+/// Move to block `n` with every earlier block already expired and
+/// slashed
+fn set_block<T: Config>(n: BlockNumberFor<T>) {
+    frame_system::Pallet::<T>::set_block_number(n);
+    ExpiryCursor::<T>::put(n);
+    SlashCursor::<T>::put(n);
+}
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    #[benchmark]
+    fn register_validator(
+        k: Linear<1, { T::MaxQuantumStateSize::get() }>,
+        v: Linear<0, { T::MaxActiveValidators::get() - 1 }>,
+    ) {
+        register_validators::<T>(v);
+        let caller = funded::<T>("caller", 0);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()), T::MinValidatorStake::get(), QuantumKeyType::ECDSA, vec![1; k as usize]);
+
+        assert!(ValidatorStates::<T>::contains_key(&caller));
+        assert_eq!(ActiveValidators::<T>::get().len() as u32, v + 1);
+    }
+
+    #[benchmark]
+    fn update_quantum_key(k: Linear<1, { T::MaxQuantumStateSize::get() }>) {
+        let caller = register_validators::<T>(1).remove(0);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()), QuantumKeyType::ECDSA, vec![1; k as usize]);
+
+        assert_eq!(ValidatorStates::<T>::get(&caller).unwrap().quantum_pubkey.len() as u32, k);
+    }
+
+    #[benchmark]
+    fn go_offline(v: Linear<1, { T::MaxActiveValidators::get() }>) {
+        let caller = register_validators::<T>(v).remove(0);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()));
+
+        assert_eq!(ValidatorStates::<T>::get(&caller).unwrap().status, ValidatorStatus::Offline);
+    }
+
+    #[benchmark]
+    fn go_online(v: Linear<1, { T::MaxActiveValidators::get() }>) {
+        let caller = register_validators::<T>(v).remove(0);
+        assert_ok!(QValidator::<T>::go_offline(RawOrigin::Signed(caller.clone()).into()));
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()));
+
+        assert_eq!(ValidatorStates::<T>::get(&caller).unwrap().status, ValidatorStatus::Active);
+    }
+
+    #[benchmark]
+    fn leave(v: Linear<1, { T::MaxActiveValidators::get() }>) {
+        let caller = register_validators::<T>(v).remove(0);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()));
+
+        assert!(UnbondingUntil::<T>::contains_key(&caller));
+    }
+
+    #[benchmark]
+    fn submit_operation(
+        d: Linear<1, { T::MaxQuantumStateSize::get() }>,
+        v: Linear<{ T::ValidationQuorum::get() }, { T::MaxActiveValidators::get() }>,
+    ) {
+        register_validators::<T>(v);
+        let caller: T::AccountId = account("submitter", 0, SEED);
+        let expires_at = frame_system::Pallet::<T>::block_number().saturating_add(10u32.into());
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), 0, OperationType::Measurement, vec![1; d as usize], expires_at);
+
+        assert_eq!(PendingOperations::<T>::iter_keys().count(), 1);
+    }
+
+    /// Vote finalizing an operation, with as many earlier votes against the
+    /// result as possible to record and slash
+    #[benchmark]
+    fn vote_validation() {
+        register_validators::<T>(T::MaxActiveValidators::get());
+        let operation_hash = submit::<T>();
+        let committee = QValidator::<T>::committee(&operation_hash);
+        let quorum = T::ValidationQuorum::get() as usize;
+        let rejections = (quorum - 1).min(committee.len() - quorum);
+        for (index, voter) in committee.iter().take(quorum - 1 + rejections).enumerate() {
+            assert_ok!(QValidator::<T>::vote_validation(
+                RawOrigin::Signed(voter.clone()).into(),
+                operation_hash,
+                index < quorum - 1,
+            ));
+        }
+        let caller = committee[quorum - 1 + rejections].clone();
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), operation_hash, true);
+
+        assert_eq!(QValidator::<T>::operation_status(&operation_hash), Some(ValidationStatus::Success));
+    }
+
+    #[benchmark]
+    fn withdraw_stake() {
+        let caller = register_validators::<T>(1).remove(0);
+        assert_ok!(QValidator::<T>::leave(RawOrigin::Signed(caller.clone()).into()));
+        set_block::<T>(UnbondingUntil::<T>::get(&caller).unwrap());
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller.clone()));
+
+        assert!(!ValidatorStates::<T>::contains_key(&caller));
+    }
+
+    #[benchmark]
+    fn cancel_slash() -> Result<(), BenchmarkError> {
+        let validators = register_validators::<T>(1);
+        QValidator::<T>::report_missed_votes(T::Hashing::hash_of(&0u32), validators);
+        let (apply_at, slash_id, _) = UnappliedSlashes::<T>::iter().next().unwrap();
+        let origin = T::SlashCancelOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, apply_at, slash_id);
+
+        assert!(!UnappliedSlashes::<T>::contains_key(apply_at, slash_id));
+        Ok(())
+    }
+
+    /// Apply slashes leaving every slashed validator below the minimum
+    /// stake, so each of them also leaves the active set
+    #[benchmark]
+    fn apply_slashes(s: Linear<0, { T::MaxActiveValidators::get() }>) {
+        let validators = register_validators::<T>(s);
+        QValidator::<T>::report_missed_votes(T::Hashing::hash_of(&0u32), validators);
+        let apply_at = frame_system::Pallet::<T>::block_number().saturating_add(T::SlashDeferDuration::get());
+        set_block::<T>(apply_at);
+
+        #[block]
+        {
+            QValidator::<T>::on_initialize(apply_at);
+        }
+
+        assert_eq!(UnappliedSlashes::<T>::iter_prefix(apply_at).count(), 0);
+    }
+
+    /// Expire an operation whose whole committee missed its vote
+    #[benchmark]
+    fn expire_operation() {
+        register_validators::<T>(T::MaxActiveValidators::get());
+        let operation_hash = submit::<T>();
+        let expires_at = PendingOperations::<T>::get(operation_hash).unwrap().expires_at;
+        set_block::<T>(expires_at);
+
+        #[block]
+        {
+            QValidator::<T>::on_initialize(expires_at);
+        }
+
+        assert_eq!(QValidator::<T>::operation_status(&operation_hash), Some(ValidationStatus::Expired));
+    }
+
+    #[benchmark]
+    fn prune_expired(p: Linear<0, MAX_PRUNED>) {
+        let now = frame_system::Pallet::<T>::block_number();
+        for index in 0..p {
+            let operation_hash = T::Hashing::hash_of(&index);
+            ValidatedOperations::<T>::insert(
+                operation_hash,
+                ValidationResult {
+                    operation_hash,
+                    validators: Default::default(),
+                    approvals: 0,
+                    status: ValidationStatus::Expired,
+                    result_data: None,
+                    completed_at: now,
+                },
+            );
+            PruneQueue::<T>::insert(now, operation_hash, ());
+        }
+        set_block::<T>(now);
+
+        #[block]
+        {
+            QValidator::<T>::on_initialize(now);
+        }
+
+        assert_eq!(ValidatedOperations::<T>::iter_keys().count(), 0);
+    }
+
+    impl_benchmark_test_suite!(QValidator, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -3,17 +3,47 @@
 //! This pallet implements the core quantum validator framework that ensures
 //! proper validation of quantum operations across all parachains in the
 //! Matrix-Magiq ecosystem.
+//!
+//! The storage layout is incompatible with the original skeleton of this
+//! pallet. That skeleton had no calls and no genesis config, so no chain
+//! could hold state in it, and chains running this pallet start from a
+//! fresh genesis instead of migrating.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use pallet::*;
 
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
 #[frame_support::pallet]
 pub mod pallet {
-    use frame_support::pallet_prelude::*;
+    use frame_support::{
+        pallet_prelude::*,
+        traits::{
+            fungible::{self, BalancedHold, Inspect, MutateHold},
+            tokens::Precision,
+            Imbalance, OnUnbalanced, Randomness,
+        },
+    };
     use frame_system::pallet_prelude::*;
+    use sp_runtime::{
+        traits::{AtLeast32BitUnsigned, Hash, Saturating, TrailingZeroInput, UniqueSaturatedInto, Zero},
+        PerThing, Perbill,
+    };
     use sp_std::vec::Vec;
 
+    use crate::WeightInfo;
+
     #[pallet::pallet]
     pub struct Pallet<T>(_);
 
@@ -21,10 +51,88 @@ pub mod pallet {
     pub trait Config: frame_system::Config {
         /// The overarching event type.
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Currency holding validator stake
+        type Currency: fungible::Mutate<Self::AccountId>
+            + MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>
+            + BalancedHold<Self::AccountId>;
+
+        /// The overarching hold reason.
+        type RuntimeHoldReason: From<HoldReason>;
+
+        /// Minimum stake of a validator
+        #[pallet::constant]
+        type MinValidatorStake: Get<BalanceOf<Self>>;
+
+        /// Number of blocks a leaving validator's stake stays held
+        #[pallet::constant]
+        type UnbondingPeriod: Get<BlockNumberFor<Self>>;
         
         /// Maximum size of quantum state data
+        ///
+        /// Hybrid quantum keys need 1986 bytes.
         #[pallet::constant]
         type MaxQuantumStateSize: Get<u32>;
+
+        /// Maximum number of active validators
+        #[pallet::constant]
+        type MaxActiveValidators: Get<u32>;
+
+        /// Source of randomness for committee selection
+        type Randomness: Randomness<Self::Hash, BlockNumberFor<Self>>;
+
+        /// Number of agreeing votes that finalizes a validation
+        #[pallet::constant]
+        type ValidationQuorum: Get<u32>;
+
+        /// Handler for slashed stake, e.g. a treasury
+        type Slash: OnUnbalanced<CreditOf<Self>>;
+
+        /// Share of held stake slashed for voting against the finalized
+        /// quorum result
+        #[pallet::constant]
+        type WrongVoteSlash: Get<Perbill>;
+
+        /// Share of held stake slashed for voting twice with conflicting
+        /// results
+        #[pallet::constant]
+        type EquivocationSlash: Get<Perbill>;
+
+        /// Share of held stake slashed for not voting on an assigned
+        /// operation
+        #[pallet::constant]
+        type MissedVoteSlash: Get<Perbill>;
+
+        /// Number of blocks a slash is deferred, during which it can be
+        /// cancelled
+        #[pallet::constant]
+        type SlashDeferDuration: Get<BlockNumberFor<Self>>;
+
+        /// Origin allowed to cancel deferred slashes
+        type SlashCancelOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Share of a validator's reputation retained per block
+        #[pallet::constant]
+        type ReputationRetention: Get<Perbill>;
+
+        /// Weight of the latest finalized vote in a validator's reputation
+        #[pallet::constant]
+        type ReputationSmoothing: Get<Perbill>;
+
+        /// Number of blocks expired validation results are kept
+        #[pallet::constant]
+        type RetentionPeriod: Get<BlockNumberFor<Self>>;
+
+        /// Maximum weight spent expiring operations per block
+        #[pallet::constant]
+        type MaxExpiryWeight: Get<Weight>;
+
+        /// Maximum weight spent applying deferred slashes per block
+        #[pallet::constant]
+        type MaxSlashWeight: Get<Weight>;
+
+        /// Weight information for extrinsics and hooks in this pallet.
+        type WeightInfo: WeightInfo;
     }
 
     /// Storage for quantum validator states
@@ -36,6 +144,119 @@ pub mod pallet {
         ValidatorState<T>,
     >;
 
+    /// Validators currently able to validate
+    #[pallet::storage]
+    pub type ActiveValidators<T: Config> =
+        StorageValue<_, BoundedVec<T::AccountId, T::MaxActiveValidators>, ValueQuery>;
+
+    /// Validators assigned to operations still collecting votes
+    #[pallet::storage]
+    pub type Committees<T: Config> = StorageMap<
+        _,
+        Identity,
+        T::Hash,
+        BoundedVec<T::AccountId, MaxValidatorsPerOperation>,
+    >;
+
+    /// Operations still collecting votes by assigned validator
+    #[pallet::storage]
+    pub type Assignments<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Identity,
+        T::Hash,
+        (),
+    >;
+
+    /// Block from which leaving validators may withdraw their stake
+    #[pallet::storage]
+    pub type UnbondingUntil<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BlockNumberFor<T>,
+    >;
+
+    /// Votes cast on operations still collecting votes, with the block
+    /// they were cast in
+    #[pallet::storage]
+    pub type Votes<T: Config> = StorageDoubleMap<
+        _,
+        Identity,
+        T::Hash,
+        Blake2_128Concat,
+        T::AccountId,
+        (bool, BlockNumberFor<T>),
+    >;
+
+    /// Validators already reported for equivocating on operations still
+    /// collecting votes
+    #[pallet::storage]
+    pub type Equivocations<T: Config> = StorageDoubleMap<
+        _,
+        Identity,
+        T::Hash,
+        Blake2_128Concat,
+        T::AccountId,
+        (),
+    >;
+
+    /// Pending operations by the block they expire in
+    #[pallet::storage]
+    pub type ExpiryQueue<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        Identity,
+        T::Hash,
+        (),
+    >;
+
+    /// Earliest block whose expiry queue may not be fully processed
+    ///
+    /// Set at genesis. When the pallet is added to a live chain, the first
+    /// block it runs in sets it, since no operation expires earlier.
+    #[pallet::storage]
+    pub type ExpiryCursor<T: Config> = StorageValue<_, BlockNumberFor<T>, OptionQuery>;
+
+    /// Expired operations by the block their result is pruned in
+    #[pallet::storage]
+    pub type PruneQueue<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        Identity,
+        T::Hash,
+        (),
+    >;
+
+    /// Deferred slashes by the block they are applied in
+    #[pallet::storage]
+    pub type UnappliedSlashes<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        Twox64Concat,
+        SlashId,
+        UnappliedSlash<T>,
+    >;
+
+    /// Earliest block whose deferred slashes may not all be applied
+    ///
+    /// Set at genesis, or by the first block the pallet runs in.
+    #[pallet::storage]
+    pub type SlashCursor<T: Config> = StorageValue<_, BlockNumberFor<T>, OptionQuery>;
+
+    /// Identifier of the next reported slash
+    #[pallet::storage]
+    pub type NextSlashId<T: Config> = StorageValue<_, SlashId, ValueQuery>;
+
+    /// Number of deferred slashes of a validator not yet applied or
+    /// cancelled
+    #[pallet::storage]
+    pub type PendingSlashes<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
     /// Storage for quantum operations to be validated
     #[pallet::storage]
     pub type PendingOperations<T: Config> = StorageMap<
@@ -45,7 +266,8 @@ pub mod pallet {
         QuantumOperation<T>,
     >;
 
-    /// Storage for validated quantum operations
+    /// Storage for validation results, including validations still
+    /// collecting votes
     #[pallet::storage]
     pub type ValidatedOperations<T: Config> = StorageMap<
         _,
@@ -56,21 +278,79 @@ pub mod pallet {
 
     /// Validator state representation
     #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
     pub struct ValidatorState<T: Config> {
         /// Current stake amount
         pub stake: BalanceOf<T>,
         /// Validator status
         pub status: ValidatorStatus,
-        /// Quantum public key
+        /// Signature scheme of `quantum_pubkey`
+        pub key_type: QuantumKeyType,
+        /// Quantum public key, a SCALE-encoded `HybridPublicKey` for hybrid
+        /// keys and opaque otherwise
         pub quantum_pubkey: BoundedVec<u8, T::MaxQuantumStateSize>,
         /// Performance metrics
         pub metrics: ValidatorMetrics,
+        /// Reliability score
+        pub reputation: Reputation<BlockNumberFor<T>>,
         /// Last updated block
-        pub last_update: T::BlockNumber,
+        pub last_update: BlockNumberFor<T>,
+    }
+
+    /// Signature scheme of a validator's quantum public key
+    ///
+    /// Stored with the key, so JAM authorization verifies each validator's
+    /// signatures with the scheme it registered.
+    #[derive(
+        Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen, serde::Serialize,
+        serde::Deserialize,
+    )]
+    #[allow(clippy::upper_case_acronyms)]
+    pub enum QuantumKeyType {
+        /// Ed25519 or ECDSA over secp256k1, told apart by key length
+        ECDSA,
+        /// Dilithium
+        LatticeBasedKEM,
+        /// Multivariate, with no signature scheme yet
+        Multivariate,
+        /// SPHINCS+
+        HashBased,
+        /// SCALE-encoded `HybridPublicKey`
+        Hybrid,
+    }
+
+    /// Composite Ed25519 and Dilithium3 quantum public key
+    ///
+    /// Validators moving to post-quantum keys register one of these, SCALE
+    /// encoded, as their `quantum_pubkey` with the `Hybrid` key type. JAM
+    /// authorization accepts a signature for it only if both the Ed25519
+    /// and the Dilithium halves verify. This is the only definition of the
+    /// format: the JAM authorizer decodes keys with it too.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+    pub struct HybridPublicKey {
+        /// Ed25519 public key
+        pub ed25519: [u8; 32],
+        /// Dilithium public key
+        pub dilithium: Vec<u8>,
+    }
+
+    impl HybridPublicKey {
+        /// Dilithium3 public key length
+        pub const DILITHIUM_KEY_LENGTH: usize = 1952;
+
+        /// Decode a hybrid key from a quantum public key, rejecting
+        /// trailing bytes and Dilithium keys of the wrong length
+        pub fn from_quantum_pubkey(mut quantum_pubkey: &[u8]) -> Option<Self> {
+            let key = Self::decode(&mut quantum_pubkey).ok()?;
+            (quantum_pubkey.is_empty() && key.dilithium.len() == Self::DILITHIUM_KEY_LENGTH).then_some(key)
+        }
     }
 
     /// Validator status
-    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[derive(
+        Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen, serde::Serialize,
+        serde::Deserialize,
+    )]
     pub enum ValidatorStatus {
         /// Active and ready to validate
         Active,
@@ -82,8 +362,25 @@ pub mod pallet {
         Leaving,
     }
 
+    impl ValidatorStatus {
+        /// Whether the validator lifecycle allows moving to `to`
+        ///
+        /// Active and offline validators may switch between each other and
+        /// may be slashed or leave; slashed validators may only leave.
+        /// Leaving is final.
+        pub fn can_transition_to(&self, to: ValidatorStatus) -> bool {
+            matches!(
+                (self, to),
+                (Self::Active, Self::Offline | Self::Slashed | Self::Leaving)
+                    | (Self::Offline, Self::Active | Self::Slashed | Self::Leaving)
+                    | (Self::Slashed, Self::Leaving)
+            )
+        }
+    }
+
     /// Validator performance metrics
     #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen, Default)]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
     pub struct ValidatorMetrics {
         /// Total operations validated
         pub operations_validated: u64,
@@ -91,12 +388,60 @@ pub mod pallet {
         pub successful_validations: u64,
         /// Failed validations
         pub failed_validations: u64,
-        /// Average validation time (in milliseconds)
-        pub avg_validation_time_ms: u32,
+        /// Sum of the validation latencies in blocks from submission
+        pub total_validation_blocks: u64,
+    }
+
+    impl ValidatorMetrics {
+        /// Average validation latency in blocks from submission, rounded
+        /// down
+        pub fn avg_validation_blocks(&self) -> u32 {
+            self.total_validation_blocks
+                .checked_div(self.operations_validated)
+                .unwrap_or_default()
+                .unique_saturated_into()
+        }
+    }
+
+    /// Reputation of newly registered validators
+    pub const INITIAL_REPUTATION: Perbill = Perbill::from_percent(50);
+
+    /// Reliability score of a validator
+    ///
+    /// The score moves toward 100% with every finalized vote agreeing with
+    /// the result and toward 0% with every vote against it, and decays
+    /// toward 0% while the validator does not validate.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct Reputation<BlockNumber> {
+        /// Score as of `updated_at`
+        pub score: Perbill,
+        /// Block the score was last updated in
+        pub updated_at: BlockNumber,
+    }
+
+    impl<BlockNumber: AtLeast32BitUnsigned + Copy> Reputation<BlockNumber> {
+        /// Initial reputation of a validator registered at `now`
+        pub fn new(now: BlockNumber) -> Self {
+            Self { score: INITIAL_REPUTATION, updated_at: now }
+        }
+
+        /// Score at `now`, retaining `retention` of it per elapsed block
+        pub fn at(&self, now: BlockNumber, retention: Perbill) -> Perbill {
+            let elapsed: u32 = now.saturating_sub(self.updated_at).unique_saturated_into();
+            self.score * retention.saturating_pow(elapsed as usize)
+        }
+
+        /// Fold a finalized vote at `now` into the score
+        pub fn record(&mut self, now: BlockNumber, correct: bool, retention: Perbill, smoothing: Perbill) {
+            let decayed = self.at(now, retention) * smoothing.left_from_one();
+            self.score = if correct { decayed.saturating_add(smoothing) } else { decayed };
+            self.updated_at = now;
+        }
     }
 
     /// Quantum operation to be validated
-    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
     pub struct QuantumOperation<T: Config> {
         /// Operation submitter
         pub submitter: T::AccountId,
@@ -107,9 +452,9 @@ pub mod pallet {
         /// Operation data (encoded quantum state)
         pub data: BoundedVec<u8, T::MaxQuantumStateSize>,
         /// Submission block number
-        pub submitted_at: T::BlockNumber,
+        pub submitted_at: BlockNumberFor<T>,
         /// Expiration block number
-        pub expires_at: T::BlockNumber,
+        pub expires_at: BlockNumberFor<T>,
     }
 
     /// Types of quantum operations
@@ -130,25 +475,75 @@ pub mod pallet {
     }
 
     /// Result of a validation
-    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
     pub struct ValidationResult<T: Config> {
         /// Operation hash
         pub operation_hash: T::Hash,
         /// Validators who participated
         pub validators: BoundedVec<T::AccountId, MaxValidatorsPerOperation>,
+        /// Number of participating validators who approved the operation
+        pub approvals: u32,
         /// Status of the validation
         pub status: ValidationStatus,
         /// Result data (if any)
         pub result_data: Option<BoundedVec<u8, T::MaxQuantumStateSize>>,
         /// Block number when validation completed
-        pub completed_at: T::BlockNumber,
+        pub completed_at: BlockNumberFor<T>,
     }
 
     /// The maximum number of validators per operation
     pub type MaxValidatorsPerOperation = ConstU32<10>;
 
+    /// Maximum page size of `Pallet::active_validators`
+    pub const MAX_PAGE_SIZE: u32 = 256;
+
+    /// Validator state as exposed to runtime API and RPC clients
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+    pub struct ValidatorInfo<Balance> {
+        /// Held stake
+        pub stake: Balance,
+        /// Validator status
+        pub status: ValidatorStatus,
+        /// Signature scheme of `quantum_pubkey`
+        pub key_type: QuantumKeyType,
+        /// Quantum public key
+        pub quantum_pubkey: Vec<u8>,
+        /// Performance metrics
+        pub metrics: ValidatorMetrics,
+        /// Current reputation
+        pub reputation: Perbill,
+    }
+
+    /// Metrics aggregated over all registered validators
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, Default)]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+    pub struct AggregateMetrics<Balance> {
+        /// Registered validators, in any status
+        pub registered_validators: u32,
+        /// Active validators
+        pub active_validators: u32,
+        /// Stake held by active validators
+        pub active_stake: Balance,
+        /// Operations still collecting votes
+        pub pending_operations: u32,
+        /// Finalized votes of all validators
+        pub operations_validated: u64,
+        /// Finalized votes agreeing with the result
+        pub successful_validations: u64,
+        /// Finalized votes against the result
+        pub failed_validations: u64,
+        /// Average validation latency in blocks over all finalized votes
+        pub avg_validation_blocks: u32,
+    }
+
+    /// Randomness subject for committee selection
+    const COMMITTEE_SUBJECT: &[u8] = b"qvalidator/committee";
+
     /// Status of a validation
     #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
     pub enum ValidationStatus {
         /// Successfully validated
         Success,
@@ -161,31 +556,94 @@ pub mod pallet {
     }
 
     /// Alias for balance type
-    pub type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+    pub type BalanceOf<T> = <<T as Config>::Currency as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
 
-    /// Currency trait for the pallet
-    pub trait Currency<AccountId> {
-        /// Balance type
-        type Balance: Member + Parameter + AtLeast32BitUnsigned + Default + Copy;
-        
-        /// Get free balance
-        fn free_balance(who: &AccountId) -> Self::Balance;
-        
-        /// Transfer balance
-        fn transfer(
-            source: &AccountId,
-            dest: &AccountId,
-            value: Self::Balance,
-            existence_requirement: ExistenceRequirement,
-        ) -> DispatchResult;
+    /// Alias for credit of slashed stake
+    pub type CreditOf<T> = fungible::Credit<<T as frame_system::Config>::AccountId, <T as Config>::Currency>;
+
+    /// Identifier of a reported slash
+    pub type SlashId = u32;
+
+    /// Offence a validator is slashed for
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum SlashReason {
+        /// Voted against the finalized quorum result
+        WrongVote,
+        /// Voted twice with conflicting results
+        Equivocation,
+        /// Did not vote on an assigned operation
+        MissedVote,
+    }
+
+    /// Slash waiting for its deferral window to end
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct UnappliedSlash<T: Config> {
+        /// Slashed validator
+        pub validator: T::AccountId,
+        /// Offence committed
+        pub reason: SlashReason,
+        /// Operation the offence was committed on
+        pub operation_hash: T::Hash,
+    }
+
+    /// Reasons for the pallet to hold funds
+    #[pallet::composite_enum]
+    pub enum HoldReason {
+        /// Stake of a registered validator
+        ValidatorStake,
     }
 
-    /// Existence requirement for currency transfers
-    pub enum ExistenceRequirement {
-        /// Keep alive
-        KeepAlive,
-        /// Allow death
-        AllowDeath,
+    /// Initial validator set, registered with their stake held
+    ///
+    /// Chain specs set it under this pallet's genesis entry. The dev and
+    /// local chain specs are built by the node, whose sources are not part
+    /// of this repository, so seeding them with test validators is left to
+    /// the node and not done here.
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        /// Initial validators with their stake, key type, quantum public
+        /// key and status, which must be `Active` or `Offline`
+        pub validators: Vec<(T::AccountId, BalanceOf<T>, QuantumKeyType, Vec<u8>, ValidatorStatus)>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            for (who, stake, key_type, quantum_pubkey, status) in &self.validators {
+                assert!(!ValidatorStates::<T>::contains_key(who), "Genesis validator registered twice");
+                assert!(*stake >= T::MinValidatorStake::get(), "Genesis validator stake below MinValidatorStake");
+                assert!(
+                    matches!(status, ValidatorStatus::Active | ValidatorStatus::Offline),
+                    "Genesis validator must be Active or Offline"
+                );
+                let quantum_pubkey = Pallet::<T>::bounded_key(*key_type, quantum_pubkey.clone())
+                    .expect("Genesis validator quantum key must be non-empty, well-formed and within MaxQuantumStateSize");
+
+                if *status == ValidatorStatus::Active {
+                    ActiveValidators::<T>::try_append(who).expect("Too many active genesis validators");
+                }
+                T::Currency::hold(&HoldReason::ValidatorStake.into(), who, *stake)
+                    .expect("Genesis validator cannot hold its stake");
+
+                ValidatorStates::<T>::insert(
+                    who,
+                    ValidatorState {
+                        stake: *stake,
+                        status: *status,
+                        key_type: *key_type,
+                        quantum_pubkey,
+                        metrics: ValidatorMetrics::default(),
+                        reputation: Reputation::new(Zero::zero()),
+                        last_update: Zero::zero(),
+                    },
+                );
+            }
+
+            ExpiryCursor::<T>::put(frame_system::Pallet::<T>::block_number());
+            SlashCursor::<T>::put(frame_system::Pallet::<T>::block_number());
+        }
     }
 
     #[pallet::event]
@@ -196,6 +654,12 @@ pub mod pallet {
         
         /// A validator has updated their state
         ValidatorUpdated { validator: T::AccountId },
+
+        /// A validator has changed status
+        ValidatorStatusChanged {
+            validator: T::AccountId,
+            status: ValidatorStatus,
+        },
         
         /// A new quantum operation was submitted
         OperationSubmitted { 
@@ -203,6 +667,37 @@ pub mod pallet {
             submitter: T::AccountId 
         },
         
+        /// A validator withdrew their stake after unbonding
+        StakeWithdrawn {
+            validator: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+
+        /// A slash was reported and deferred until `apply_at`
+        SlashReported {
+            slash_id: SlashId,
+            validator: T::AccountId,
+            reason: SlashReason,
+            apply_at: BlockNumberFor<T>,
+        },
+
+        /// A deferred slash was applied
+        Slashed {
+            validator: T::AccountId,
+            reason: SlashReason,
+            amount: BalanceOf<T>,
+        },
+
+        /// A deferred slash was cancelled
+        SlashCancelled { slash_id: SlashId },
+
+        /// A validator voted on a quantum operation
+        ValidationVoteCast {
+            operation_hash: T::Hash,
+            validator: T::AccountId,
+            approve: bool,
+        },
+        
         /// A quantum operation was validated
         OperationValidated { 
             operation_hash: T::Hash, 
@@ -241,8 +736,771 @@ pub mod pallet {
         
         /// Quantum state too large
         QuantumStateTooLarge,
+
+        /// Status change not allowed by the validator lifecycle
+        InvalidStatusTransition,
+
+        /// Validator already voted on the operation
+        AlreadyVoted,
+
+        /// Validator is not leaving the validator set
+        NotLeaving,
+
+        /// Unbonding period has not ended yet
+        StillUnbonding,
+
+        /// No deferred slash with the given identifier
+        SlashNotFound,
+
+        /// Fewer active validators than the validation quorum
+        NotEnoughValidators,
+
+        /// Validator is not in the operation's committee
+        NotAssigned,
+
+        /// Validator is still assigned to operations collecting votes
+        StillAssigned,
+
+        /// Validator has deferred slashes not yet applied
+        SlashesPending,
+
+        /// Quantum public key of the hybrid key type does not decode as a
+        /// hybrid key
+        InvalidHybridKey,
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+            Self::apply_slashes(now)
+                .saturating_add(Self::expire_operations(now))
+                .saturating_add(Self::prune_expired(now))
+        }
+
+        fn integrity_test() {
+            let quorum = T::ValidationQuorum::get();
+            assert!(
+                quorum > 0 && quorum <= MaxValidatorsPerOperation::get(),
+                "ValidationQuorum must be between 1 and MaxValidatorsPerOperation"
+            );
+            let defer = T::SlashDeferDuration::get();
+            assert!(
+                !defer.is_zero() && defer < T::UnbondingPeriod::get(),
+                "SlashDeferDuration must be non-zero and shorter than UnbondingPeriod"
+            );
+            assert!(
+                T::MaxExpiryWeight::get()
+                    .all_gte(Self::expiry_weight().saturating_add(T::DbWeight::get().reads_writes(2, 1))),
+                "MaxExpiryWeight must allow expiring at least one operation"
+            );
+            assert!(
+                T::MaxSlashWeight::get().all_gte(
+                    T::WeightInfo::apply_slashes(0)
+                        .saturating_add(Self::slash_weight())
+                        .saturating_add(T::DbWeight::get().reads(1))
+                ),
+                "MaxSlashWeight must allow applying at least one slash"
+            );
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Register the caller as an active validator with the given stake
+        /// and quantum public key of type `key_type`
+        ///
+        /// The stake is held until the validator leaves and withdraws it.
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::WeightInfo::register_validator(quantum_pubkey.len() as u32, T::MaxActiveValidators::get()))]
+        pub fn register_validator(
+            origin: OriginFor<T>,
+            stake: BalanceOf<T>,
+            key_type: QuantumKeyType,
+            quantum_pubkey: Vec<u8>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(!ValidatorStates::<T>::contains_key(&who), Error::<T>::ValidatorAlreadyRegistered);
+            ensure!(stake >= T::MinValidatorStake::get(), Error::<T>::InsufficientStake);
+            let quantum_pubkey = Self::bounded_key(key_type, quantum_pubkey)?;
+            let now = frame_system::Pallet::<T>::block_number();
+
+            ActiveValidators::<T>::try_append(&who).map_err(|_| Error::<T>::TooManyValidators)?;
+            T::Currency::hold(&HoldReason::ValidatorStake.into(), &who, stake)
+                .map_err(|_| Error::<T>::InsufficientStake)?;
+
+            ValidatorStates::<T>::insert(
+                &who,
+                ValidatorState {
+                    stake,
+                    status: ValidatorStatus::Active,
+                    key_type,
+                    quantum_pubkey,
+                    metrics: ValidatorMetrics::default(),
+                    reputation: Reputation::new(now),
+                    last_update: now,
+                },
+            );
+
+            Self::deposit_event(Event::ValidatorRegistered { validator: who });
+            Ok(())
+        }
+
+        /// Replace the quantum public key of an active or offline validator,
+        /// possibly with a key of another type
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::update_quantum_key(quantum_pubkey.len() as u32))]
+        pub fn update_quantum_key(
+            origin: OriginFor<T>,
+            key_type: QuantumKeyType,
+            quantum_pubkey: Vec<u8>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let quantum_pubkey = Self::bounded_key(key_type, quantum_pubkey)?;
+
+            ValidatorStates::<T>::try_mutate(&who, |state| -> DispatchResult {
+                let state = state.as_mut().ok_or(Error::<T>::ValidatorNotFound)?;
+                ensure!(
+                    matches!(state.status, ValidatorStatus::Active | ValidatorStatus::Offline),
+                    Error::<T>::ValidatorNotActive
+                );
+                state.key_type = key_type;
+                state.quantum_pubkey = quantum_pubkey;
+                state.last_update = frame_system::Pallet::<T>::block_number();
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::ValidatorUpdated { validator: who });
+            Ok(())
+        }
+
+        /// Temporarily stop validating
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::go_offline(T::MaxActiveValidators::get()))]
+        pub fn go_offline(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                Self::validator_status(&who)? == ValidatorStatus::Active,
+                Error::<T>::ValidatorNotActive
+            );
+
+            Self::set_status(&who, ValidatorStatus::Offline)
+        }
+
+        /// Resume validating after going offline
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::go_online(T::MaxActiveValidators::get()))]
+        pub fn go_online(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                Self::validator_status(&who)? == ValidatorStatus::Offline,
+                Error::<T>::InvalidStatusTransition
+            );
+
+            Self::set_status(&who, ValidatorStatus::Active)
+        }
+
+        /// Start leaving the validator set
+        ///
+        /// The stake can be withdrawn once `UnbondingPeriod` blocks have
+        /// passed.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::leave(T::MaxActiveValidators::get()))]
+        pub fn leave(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::set_status(&who, ValidatorStatus::Leaving)?;
+            let now = frame_system::Pallet::<T>::block_number();
+            UnbondingUntil::<T>::insert(&who, now.saturating_add(T::UnbondingPeriod::get()));
+            Ok(())
+        }
+
+        /// Submit a quantum operation for validation
+        ///
+        /// A committee of up to `MaxValidatorsPerOperation` active
+        /// validators is assigned to validate it.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::submit_operation(data.len() as u32, T::MaxActiveValidators::get()))]
+        pub fn submit_operation(
+            origin: OriginFor<T>,
+            parachain_id: u32,
+            op_type: OperationType,
+            data: Vec<u8>,
+            expires_at: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            let submitter = ensure_signed(origin)?;
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(expires_at > now, Error::<T>::OperationExpired);
+            ensure!(!data.is_empty(), Error::<T>::InvalidQuantumState);
+
+            let operation = QuantumOperation::<T> {
+                submitter: submitter.clone(),
+                parachain_id,
+                op_type,
+                data: data.try_into().map_err(|_| Error::<T>::QuantumStateTooLarge)?,
+                submitted_at: now,
+                expires_at,
+            };
+
+            let operation_hash = T::Hashing::hash_of(&operation);
+            ensure!(
+                !PendingOperations::<T>::contains_key(operation_hash)
+                    && !ValidatedOperations::<T>::contains_key(operation_hash),
+                Error::<T>::OperationAlreadyExists
+            );
+
+            let committee = Self::select_committee(&operation_hash);
+            ensure!(committee.len() as u32 >= T::ValidationQuorum::get(), Error::<T>::NotEnoughValidators);
+            for member in &committee {
+                Assignments::<T>::insert(member, operation_hash, ());
+            }
+            Committees::<T>::insert(operation_hash, committee);
+
+            PendingOperations::<T>::insert(operation_hash, operation);
+            ExpiryQueue::<T>::insert(expires_at, operation_hash, ());
+
+            Self::deposit_event(Event::OperationSubmitted { operation_hash, submitter });
+            Ok(())
+        }
+
+        /// Vote on the validity of a pending operation
+        ///
+        /// The validation is finalized as soon as `ValidationQuorum`
+        /// validators agree on it, and validators who voted against the
+        /// result are slashed. A second vote conflicting with the first is
+        /// slashed as equivocation, once per operation, and not counted.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::vote_validation())]
+        pub fn vote_validation(origin: OriginFor<T>, operation_hash: T::Hash, approve: bool) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                Self::validator_status(&who)? == ValidatorStatus::Active,
+                Error::<T>::ValidatorNotActive
+            );
+
+            let operation = PendingOperations::<T>::get(operation_hash).ok_or(Error::<T>::OperationNotFound)?;
+            ensure!(Assignments::<T>::contains_key(&who, operation_hash), Error::<T>::NotAssigned);
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(operation.expires_at > now, Error::<T>::OperationExpired);
+
+            let mut result = Self::validation_result(operation_hash, now);
+            if let Some((previous, _)) = Votes::<T>::get(operation_hash, &who) {
+                ensure!(
+                    previous != approve && !Equivocations::<T>::contains_key(operation_hash, &who),
+                    Error::<T>::AlreadyVoted
+                );
+                Equivocations::<T>::insert(operation_hash, &who, ());
+                Self::report_slash(who, SlashReason::Equivocation, operation_hash);
+                return Ok(());
+            }
+            Votes::<T>::insert(operation_hash, &who, (approve, now));
+            result.validators.try_push(who.clone()).map_err(|_| Error::<T>::TooManyValidators)?;
+            if approve {
+                result.approvals += 1;
+            }
+            let rejections = result.validators.len() as u32 - result.approvals;
+
+            let quorum = T::ValidationQuorum::get();
+            if result.approvals >= quorum {
+                result.status = ValidationStatus::Success;
+            } else if rejections >= quorum {
+                result.status = ValidationStatus::Failed;
+            }
+            result.completed_at = now;
+
+            let status = result.status;
+            let validators = result.validators.clone();
+            ValidatedOperations::<T>::insert(operation_hash, result);
+            Self::deposit_event(Event::ValidationVoteCast { operation_hash, validator: who, approve });
+
+            if status != ValidationStatus::InProgress {
+                PendingOperations::<T>::remove(operation_hash);
+                Self::clear_committee(operation_hash);
+                let _ = Equivocations::<T>::clear_prefix(operation_hash, MaxValidatorsPerOperation::get(), None);
+                for validator in validators {
+                    let Some((approved, voted_at)) = Votes::<T>::take(operation_hash, &validator) else {
+                        continue;
+                    };
+                    let correct = approved == (status == ValidationStatus::Success);
+                    Self::record_validation(&validator, correct, voted_at.saturating_sub(operation.submitted_at), now);
+                    if !correct {
+                        Self::report_slash(validator, SlashReason::WrongVote, operation_hash);
+                    }
+                }
+                Self::deposit_event(Event::OperationValidated { operation_hash, status });
+            }
+            Ok(())
+        }
+
+        /// Release the stake of a validator that has finished unbonding and
+        /// remove it from the validator set
+        ///
+        /// The stake stays held while the validator is assigned to
+        /// operations still collecting votes or has slashes pending, so
+        /// every slash it incurred is applied first.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::withdraw_stake())]
+        pub fn withdraw_stake(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let state = ValidatorStates::<T>::get(&who).ok_or(Error::<T>::ValidatorNotFound)?;
+            let unbonding_until = UnbondingUntil::<T>::get(&who).ok_or(Error::<T>::NotLeaving)?;
+            ensure!(
+                frame_system::Pallet::<T>::block_number() >= unbonding_until,
+                Error::<T>::StillUnbonding
+            );
+            ensure!(Assignments::<T>::iter_key_prefix(&who).next().is_none(), Error::<T>::StillAssigned);
+            ensure!(PendingSlashes::<T>::get(&who) == 0, Error::<T>::SlashesPending);
+
+            let amount = T::Currency::release(
+                &HoldReason::ValidatorStake.into(),
+                &who,
+                state.stake,
+                Precision::BestEffort,
+            )?;
+            ValidatorStates::<T>::remove(&who);
+            UnbondingUntil::<T>::remove(&who);
+
+            Self::deposit_event(Event::StakeWithdrawn { validator: who, amount });
+            Ok(())
+        }
+
+        /// Cancel a deferred slash before it is applied
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::cancel_slash())]
+        pub fn cancel_slash(origin: OriginFor<T>, apply_at: BlockNumberFor<T>, slash_id: SlashId) -> DispatchResult {
+            T::SlashCancelOrigin::ensure_origin(origin)?;
+
+            let slash = UnappliedSlashes::<T>::take(apply_at, slash_id).ok_or(Error::<T>::SlashNotFound)?;
+            Self::settle_slash(&slash.validator);
+
+            Self::deposit_event(Event::SlashCancelled { slash_id });
+            Ok(())
+        }
     }
 
-    // Pallet callable functions will be added here in the next script
-    // This is a skeleton implementation
+    impl<T: Config> Pallet<T> {
+        /// Bound a quantum public key of type `key_type`, rejecting empty and
+        /// oversized keys and malformed hybrid keys
+        fn bounded_key(
+            key_type: QuantumKeyType,
+            quantum_pubkey: Vec<u8>,
+        ) -> Result<BoundedVec<u8, T::MaxQuantumStateSize>, DispatchError> {
+            ensure!(!quantum_pubkey.is_empty(), Error::<T>::InvalidQuantumState);
+            ensure!(
+                key_type != QuantumKeyType::Hybrid || HybridPublicKey::from_quantum_pubkey(&quantum_pubkey).is_some(),
+                Error::<T>::InvalidHybridKey
+            );
+            Ok(quantum_pubkey.try_into().map_err(|_| Error::<T>::QuantumStateTooLarge)?)
+        }
+
+        /// Report validators assigned to an operation that did not vote on
+        /// it for slashing
+        pub fn report_missed_votes(operation_hash: T::Hash, validators: impl IntoIterator<Item = T::AccountId>) {
+            for validator in validators {
+                Self::report_slash(validator, SlashReason::MissedVote, operation_hash);
+            }
+        }
+
+        /// Defer a slash by `SlashDeferDuration` blocks
+        fn report_slash(validator: T::AccountId, reason: SlashReason, operation_hash: T::Hash) {
+            let slash_id = NextSlashId::<T>::mutate(|next| {
+                let id = *next;
+                *next = next.wrapping_add(1);
+                id
+            });
+            let apply_at = frame_system::Pallet::<T>::block_number().saturating_add(T::SlashDeferDuration::get());
+
+            PendingSlashes::<T>::mutate(&validator, |pending| pending.saturating_inc());
+            UnappliedSlashes::<T>::insert(
+                apply_at,
+                slash_id,
+                UnappliedSlash { validator: validator.clone(), reason, operation_hash },
+            );
+            Self::deposit_event(Event::SlashReported { slash_id, validator, reason, apply_at });
+        }
+
+        /// Stop counting a deferred slash as pending once it is applied or
+        /// cancelled
+        fn settle_slash(validator: &T::AccountId) {
+            PendingSlashes::<T>::mutate_exists(validator, |pending| {
+                *pending = pending.and_then(|count| count.checked_sub(1)).filter(|count| *count > 0);
+            });
+        }
+
+        /// Slash the configured share of the validator's held stake
+        ///
+        /// Equivocating validators, and validators left with less than
+        /// `MinValidatorStake`, are moved to `Slashed`.
+        fn apply_slash(slash: UnappliedSlash<T>) {
+            let UnappliedSlash { validator, reason, .. } = slash;
+            let Some(mut state) = ValidatorStates::<T>::get(&validator) else {
+                return;
+            };
+
+            let fraction = match reason {
+                SlashReason::WrongVote => T::WrongVoteSlash::get(),
+                SlashReason::Equivocation => T::EquivocationSlash::get(),
+                SlashReason::MissedVote => T::MissedVoteSlash::get(),
+            };
+            let (credit, _) =
+                T::Currency::slash(&HoldReason::ValidatorStake.into(), &validator, fraction * state.stake);
+            let amount = credit.peek();
+            T::Slash::on_unbalanced(credit);
+
+            state.stake = state.stake.saturating_sub(amount);
+            let demote = reason == SlashReason::Equivocation || state.stake < T::MinValidatorStake::get();
+            if demote && state.status.can_transition_to(ValidatorStatus::Slashed) {
+                // Leaving the active set cannot fail
+                let _ = Self::update_active(&validator, state.status, ValidatorStatus::Slashed);
+                state.status = ValidatorStatus::Slashed;
+                Self::deposit_event(Event::ValidatorStatusChanged {
+                    validator: validator.clone(),
+                    status: ValidatorStatus::Slashed,
+                });
+            }
+            state.last_update = frame_system::Pallet::<T>::block_number();
+            ValidatorStates::<T>::insert(&validator, state);
+
+            Self::deposit_event(Event::Slashed { validator, reason, amount });
+        }
+
+        /// Current result of an operation, or a new one without votes
+        fn validation_result(operation_hash: T::Hash, now: BlockNumberFor<T>) -> ValidationResult<T> {
+            ValidatedOperations::<T>::get(operation_hash).unwrap_or_else(|| ValidationResult {
+                operation_hash,
+                validators: Default::default(),
+                approvals: 0,
+                status: ValidationStatus::InProgress,
+                result_data: None,
+                completed_at: now,
+            })
+        }
+
+        /// Apply the slashes deferred until `now`, oldest first, within
+        /// `MaxSlashWeight`
+        ///
+        /// Slashes left over once the budget is spent are applied in the
+        /// following blocks, and can still be cancelled until then.
+        fn apply_slashes(now: BlockNumberFor<T>) -> Weight {
+            let budget = T::MaxSlashWeight::get();
+            let db = T::DbWeight::get();
+            let mut weight = T::WeightInfo::apply_slashes(0);
+            let mut block = SlashCursor::<T>::get().unwrap_or(now);
+
+            'blocks: while block <= now {
+                let next = weight.saturating_add(db.reads(1));
+                if next.any_gt(budget) {
+                    break;
+                }
+                weight = next;
+                for (slash_id, slash) in UnappliedSlashes::<T>::iter_prefix(block) {
+                    let next = weight.saturating_add(Self::slash_weight());
+                    if next.any_gt(budget) {
+                        break 'blocks;
+                    }
+                    UnappliedSlashes::<T>::remove(block, slash_id);
+                    Self::settle_slash(&slash.validator);
+                    Self::apply_slash(slash);
+                    weight = next;
+                }
+                block.saturating_inc();
+            }
+
+            SlashCursor::<T>::put(block);
+            weight
+        }
+
+        /// Weight of applying a single slash
+        fn slash_weight() -> Weight {
+            T::WeightInfo::apply_slashes(1).saturating_sub(T::WeightInfo::apply_slashes(0))
+        }
+
+        /// Prune the expired results whose retention period ends at `now`
+        fn prune_expired(now: BlockNumberFor<T>) -> Weight {
+            let mut pruned = 0u32;
+            for (operation_hash, ()) in PruneQueue::<T>::drain_prefix(now) {
+                ValidatedOperations::<T>::remove(operation_hash);
+                pruned += 1;
+            }
+            T::WeightInfo::prune_expired(pruned)
+        }
+
+        /// Expire overdue operations, oldest first, within `MaxExpiryWeight`
+        ///
+        /// Operations left over once the budget is spent are expired in
+        /// the following blocks.
+        fn expire_operations(now: BlockNumberFor<T>) -> Weight {
+            let budget = T::MaxExpiryWeight::get();
+            let db = T::DbWeight::get();
+            let mut weight = db.reads_writes(1, 1);
+            let mut block = ExpiryCursor::<T>::get().unwrap_or(now);
+
+            'blocks: while block <= now {
+                let next = weight.saturating_add(db.reads(1));
+                if next.any_gt(budget) {
+                    break;
+                }
+                weight = next;
+                for operation_hash in ExpiryQueue::<T>::iter_key_prefix(block) {
+                    let next = weight.saturating_add(Self::expiry_weight());
+                    if next.any_gt(budget) {
+                        break 'blocks;
+                    }
+                    ExpiryQueue::<T>::remove(block, operation_hash);
+                    Self::expire(operation_hash, now);
+                    weight = next;
+                }
+                block.saturating_inc();
+            }
+
+            ExpiryCursor::<T>::put(block);
+            weight
+        }
+
+        /// Weight of expiring a single operation
+        fn expiry_weight() -> Weight {
+            T::WeightInfo::expire_operation()
+        }
+
+        /// Move an operation still collecting votes to `ValidatedOperations`
+        /// as expired, reporting committee members who did not vote
+        fn expire(operation_hash: T::Hash, now: BlockNumberFor<T>) {
+            if PendingOperations::<T>::take(operation_hash).is_none() {
+                return;
+            }
+            let _ = Votes::<T>::clear_prefix(operation_hash, MaxValidatorsPerOperation::get(), None);
+            let _ = Equivocations::<T>::clear_prefix(operation_hash, MaxValidatorsPerOperation::get(), None);
+
+            let mut result = Self::validation_result(operation_hash, now);
+            let missed = Self::clear_committee(operation_hash)
+                .into_iter()
+                .filter(|member| !result.validators.contains(member));
+            Self::report_missed_votes(operation_hash, missed);
+
+            result.status = ValidationStatus::Expired;
+            result.completed_at = now;
+            ValidatedOperations::<T>::insert(operation_hash, result);
+            PruneQueue::<T>::insert(now.saturating_add(T::RetentionPeriod::get()), operation_hash, ());
+
+            Self::deposit_event(Event::OperationValidated { operation_hash, status: ValidationStatus::Expired });
+        }
+
+        /// Current reputation of a validator
+        pub fn reputation(who: &T::AccountId) -> Option<Perbill> {
+            let state = ValidatorStates::<T>::get(who)?;
+            Some(state.reputation.at(frame_system::Pallet::<T>::block_number(), T::ReputationRetention::get()))
+        }
+
+        /// Update the metrics and reputation of a validator for a finalized
+        /// vote cast `latency` blocks after submission
+        fn record_validation(who: &T::AccountId, correct: bool, latency: BlockNumberFor<T>, now: BlockNumberFor<T>) {
+            ValidatorStates::<T>::mutate(who, |state| {
+                let Some(state) = state else {
+                    return;
+                };
+
+                let metrics = &mut state.metrics;
+                metrics.operations_validated.saturating_inc();
+                if correct {
+                    metrics.successful_validations.saturating_inc();
+                } else {
+                    metrics.failed_validations.saturating_inc();
+                }
+                metrics.total_validation_blocks.saturating_accrue(latency.unique_saturated_into());
+
+                state.reputation.record(now, correct, T::ReputationRetention::get(), T::ReputationSmoothing::get());
+            });
+        }
+
+        /// State of a registered validator
+        pub fn validator_info(who: &T::AccountId) -> Option<ValidatorInfo<BalanceOf<T>>> {
+            let state = ValidatorStates::<T>::get(who)?;
+            let now = frame_system::Pallet::<T>::block_number();
+            Some(ValidatorInfo {
+                stake: state.stake,
+                status: state.status,
+                key_type: state.key_type,
+                quantum_pubkey: state.quantum_pubkey.into_inner(),
+                metrics: state.metrics,
+                reputation: state.reputation.at(now, T::ReputationRetention::get()),
+            })
+        }
+
+        /// Hybrid quantum key of a registered validator, if it has one
+        pub fn hybrid_key(who: &T::AccountId) -> Option<HybridPublicKey> {
+            let state = ValidatorStates::<T>::get(who).filter(|state| state.key_type == QuantumKeyType::Hybrid)?;
+            HybridPublicKey::from_quantum_pubkey(&state.quantum_pubkey)
+        }
+
+        /// Page of at most `MAX_PAGE_SIZE` active validators from `start`
+        pub fn active_validators(start: u32, limit: u32) -> Vec<T::AccountId> {
+            ActiveValidators::<T>::get()
+                .into_iter()
+                .skip(start as usize)
+                .take(limit.min(MAX_PAGE_SIZE) as usize)
+                .collect()
+        }
+
+        /// Key types, quantum public keys and stake of the active
+        /// validators, in active set order
+        ///
+        /// Stake-weighted JAM authorization uses these as signer weights.
+        pub fn staked_keys() -> Vec<(T::AccountId, QuantumKeyType, Vec<u8>, BalanceOf<T>)> {
+            ActiveValidators::<T>::get()
+                .into_iter()
+                .filter_map(|who| {
+                    let state = ValidatorStates::<T>::get(&who)?;
+                    Some((who, state.key_type, state.quantum_pubkey.into_inner(), state.stake))
+                })
+                .collect()
+        }
+
+        /// Validation status of an operation, `InProgress` while it is
+        /// collecting votes
+        pub fn operation_status(operation_hash: &T::Hash) -> Option<ValidationStatus> {
+            ValidatedOperations::<T>::get(operation_hash)
+                .map(|result| result.status)
+                .or_else(|| PendingOperations::<T>::contains_key(operation_hash).then_some(ValidationStatus::InProgress))
+        }
+
+        /// Metrics aggregated over all registered validators
+        pub fn aggregate_metrics() -> AggregateMetrics<BalanceOf<T>> {
+            let mut aggregate = AggregateMetrics::<BalanceOf<T>>::default();
+            let mut total_blocks = 0u128;
+            for (_, state) in ValidatorStates::<T>::iter() {
+                aggregate.registered_validators.saturating_inc();
+                if state.status == ValidatorStatus::Active {
+                    aggregate.active_validators.saturating_inc();
+                    aggregate.active_stake.saturating_accrue(state.stake);
+                }
+                let metrics = state.metrics;
+                aggregate.operations_validated.saturating_accrue(metrics.operations_validated);
+                aggregate.successful_validations.saturating_accrue(metrics.successful_validations);
+                aggregate.failed_validations.saturating_accrue(metrics.failed_validations);
+                total_blocks.saturating_accrue(metrics.total_validation_blocks as u128);
+            }
+            aggregate.pending_operations = PendingOperations::<T>::iter_keys().count() as u32;
+            if aggregate.operations_validated > 0 {
+                let average = total_blocks / aggregate.operations_validated as u128;
+                aggregate.avg_validation_blocks = average.unique_saturated_into();
+            }
+            aggregate
+        }
+
+        /// Operations finalized in the current block, read from its events
+        pub fn operations_validated() -> Vec<(T::Hash, ValidationStatus)>
+        where
+            <T as Config>::RuntimeEvent: TryInto<Event<T>>,
+        {
+            frame_system::Pallet::<T>::read_events_no_consensus()
+                .filter_map(|record| {
+                    let event: <T as Config>::RuntimeEvent = record.event.clone().into();
+                    match event.try_into() {
+                        Ok(Event::OperationValidated { operation_hash, status }) => Some((operation_hash, status)),
+                        _ => None,
+                    }
+                })
+                .collect()
+        }
+
+        /// Validators assigned to an operation still collecting votes
+        pub fn committee(operation_hash: &T::Hash) -> Vec<T::AccountId> {
+            Committees::<T>::get(operation_hash).map(|committee| committee.into_inner()).unwrap_or_default()
+        }
+
+        /// Operations still collecting votes that a validator is assigned to
+        pub fn assignments(who: &T::AccountId) -> Vec<T::Hash> {
+            Assignments::<T>::iter_key_prefix(who).collect()
+        }
+
+        /// Sample up to `MaxValidatorsPerOperation` distinct active
+        /// validators
+        ///
+        /// Validators are drawn with probability proportional to their stake
+        /// scaled by reputation, from randomness seeded by on-chain
+        /// randomness and the operation hash.
+        fn select_committee(operation_hash: &T::Hash) -> BoundedVec<T::AccountId, MaxValidatorsPerOperation> {
+            let now = frame_system::Pallet::<T>::block_number();
+            let retention = T::ReputationRetention::get();
+            let mut candidates: Vec<(T::AccountId, u128)> = ActiveValidators::<T>::get()
+                .into_iter()
+                .filter_map(|who| {
+                    let state = ValidatorStates::<T>::get(&who)?;
+                    let stake: u128 = state.stake.unique_saturated_into();
+                    // Every active validator keeps a chance of selection
+                    let weight = state.reputation.at(now, retention).mul_floor(stake).max(1);
+                    Some((who, weight))
+                })
+                .collect();
+
+            let (seed, _) = T::Randomness::random(&(COMMITTEE_SUBJECT, operation_hash).encode());
+            let mut committee = BoundedVec::new();
+            let mut draw = 0u32;
+            while !candidates.is_empty() && (committee.len() as u32) < MaxValidatorsPerOperation::get() {
+                let total = candidates.iter().fold(0u128, |total, (_, weight)| total.saturating_add(*weight));
+                let random = T::Hashing::hash_of(&(seed, draw));
+                let mut target = u128::decode(&mut TrailingZeroInput::new(random.as_ref())).unwrap_or_default() % total;
+
+                let index = candidates
+                    .iter()
+                    .position(|(_, weight)| {
+                        if target < *weight {
+                            return true;
+                        }
+                        target -= weight;
+                        false
+                    })
+                    .unwrap_or(candidates.len() - 1);
+                let (member, _) = candidates.remove(index);
+                // Cannot exceed the bound checked above
+                let _ = committee.try_push(member);
+                draw += 1;
+            }
+
+            committee
+        }
+
+        /// Remove the committee of an operation and its assignments
+        fn clear_committee(operation_hash: T::Hash) -> Vec<T::AccountId> {
+            let committee = Committees::<T>::take(operation_hash).unwrap_or_default();
+            for member in &committee {
+                Assignments::<T>::remove(member, operation_hash);
+            }
+            committee.into_inner()
+        }
+
+        /// Keep `ActiveValidators` in sync with a status change
+        fn update_active(who: &T::AccountId, from: ValidatorStatus, to: ValidatorStatus) -> DispatchResult {
+            ActiveValidators::<T>::try_mutate(|active| {
+                if from == ValidatorStatus::Active {
+                    active.retain(|validator| validator != who);
+                }
+                if to == ValidatorStatus::Active {
+                    active.try_push(who.clone()).map_err(|_| Error::<T>::TooManyValidators)?;
+                }
+                Ok(())
+            })
+        }
+
+        /// Status of a registered validator
+        fn validator_status(who: &T::AccountId) -> Result<ValidatorStatus, DispatchError> {
+            Ok(ValidatorStates::<T>::get(who).ok_or(Error::<T>::ValidatorNotFound)?.status)
+        }
+
+        /// Move a validator to `status` if the lifecycle allows it
+        fn set_status(who: &T::AccountId, status: ValidatorStatus) -> DispatchResult {
+            ValidatorStates::<T>::try_mutate(who, |state| -> DispatchResult {
+                let state = state.as_mut().ok_or(Error::<T>::ValidatorNotFound)?;
+                ensure!(state.status.can_transition_to(status), Error::<T>::InvalidStatusTransition);
+                Self::update_active(who, state.status, status)?;
+                state.status = status;
+                state.last_update = frame_system::Pallet::<T>::block_number();
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::ValidatorStatusChanged { validator: who.clone(), status });
+            Ok(())
+        }
+    }
 }
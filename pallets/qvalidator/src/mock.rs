@@ -0,0 +1,158 @@
+use crate as quantum_validator;
+use crate::{CreditOf, QuantumKeyType, ValidatorStatus, WeightInfo};
+use frame_support::{
+    parameter_types,
+    traits::{fungible::Balanced, ConstU32, ConstU64, Everything, OnUnbalanced, Randomness},
+    weights::{RuntimeDbWeight, Weight},
+};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, Hash, IdentityLookup},
+    BuildStorage, Perbill,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        Balances: pallet_balances,
+        QValidator: quantum_validator,
+    }
+);
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = TestDbWeight;
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Nonce = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Block = Block;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+/// Balance of every endowed account in the mock
+pub const BALANCE: u64 = 1_000;
+
+/// Account without any balance
+pub const POOR: u64 = 99;
+
+impl pallet_balances::Config for Test {
+    type MaxLocks = ();
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+    type Balance = u64;
+    type RuntimeEvent = RuntimeEvent;
+    type DustRemoval = ();
+    type ExistentialDeposit = ConstU64<1>;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type FreezeIdentifier = ();
+    type MaxFreezes = ();
+    type RuntimeHoldReason = RuntimeHoldReason;
+    type MaxHolds = ConstU32<1>;
+}
+
+/// Account receiving slashed stake
+pub const TREASURY: u64 = 100;
+
+/// Deposits slashed stake into `TREASURY`
+pub struct SlashToTreasury;
+
+impl OnUnbalanced<CreditOf<Test>> for SlashToTreasury {
+    fn on_nonzero_unbalanced(credit: CreditOf<Test>) {
+        let _ = Balances::resolve(&TREASURY, credit);
+    }
+}
+
+/// Randomness derived from the subject alone
+pub struct TestRandomness;
+
+impl Randomness<H256, u64> for TestRandomness {
+    fn random(subject: &[u8]) -> (H256, u64) {
+        (BlakeTwo256::hash(subject), System::block_number())
+    }
+}
+
+parameter_types! {
+    pub const TestDbWeight: RuntimeDbWeight = RuntimeDbWeight { read: 1, write: 1 };
+    // Expires two operations per block
+    pub storage MaxExpiryWeight: Weight = <() as WeightInfo>::expire_operation()
+        .saturating_mul(2)
+        .saturating_add(Weight::from_parts(100, 0));
+    // Applies four slashes per block
+    pub storage MaxSlashWeight: Weight = <() as WeightInfo>::apply_slashes(4)
+        .saturating_add(Weight::from_parts(100, 0));
+    pub const RetentionPeriod: u64 = 5;
+    pub const MinValidatorStake: u64 = 50;
+    pub const UnbondingPeriod: u64 = 5;
+    pub const WrongVoteSlash: Perbill = Perbill::from_percent(10);
+    pub const EquivocationSlash: Perbill = Perbill::from_percent(50);
+    pub const MissedVoteSlash: Perbill = Perbill::from_percent(5);
+    pub const SlashDeferDuration: u64 = 2;
+    pub const ReputationRetention: Perbill = Perbill::from_percent(50);
+    pub const ReputationSmoothing: Perbill = Perbill::from_percent(50);
+}
+
+impl quantum_validator::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type RuntimeHoldReason = RuntimeHoldReason;
+    type MinValidatorStake = MinValidatorStake;
+    type UnbondingPeriod = UnbondingPeriod;
+    type MaxQuantumStateSize = ConstU32<2048>;
+    type MaxActiveValidators = ConstU32<16>;
+    type Randomness = TestRandomness;
+    type ValidationQuorum = ConstU32<2>;
+    type Slash = SlashToTreasury;
+    type WrongVoteSlash = WrongVoteSlash;
+    type EquivocationSlash = EquivocationSlash;
+    type MissedVoteSlash = MissedVoteSlash;
+    type SlashDeferDuration = SlashDeferDuration;
+    type SlashCancelOrigin = EnsureRoot<u64>;
+    type ReputationRetention = ReputationRetention;
+    type ReputationSmoothing = ReputationSmoothing;
+    type RetentionPeriod = RetentionPeriod;
+    type MaxExpiryWeight = MaxExpiryWeight;
+    type MaxSlashWeight = MaxSlashWeight;
+    type WeightInfo = ();
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    new_test_ext_with_validators(vec![])
+}
+
+/// Externalities with `validators` registered at genesis
+pub fn new_test_ext_with_validators(
+    validators: Vec<(u64, u64, QuantumKeyType, Vec<u8>, ValidatorStatus)>,
+) -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+    pallet_balances::GenesisConfig::<Test> {
+        balances: (1..=20).map(|who| (who, BALANCE)).collect(),
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+    quantum_validator::GenesisConfig::<Test> { validators }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
@@ -0,0 +1,1107 @@
+use crate::{
+    mock::*, ActiveValidators, Assignments, Committees, Equivocations, Error, Event, ExpiryCursor, ExpiryQueue, HoldReason, HybridPublicKey, OperationType, PendingOperations, PendingSlashes, QuantumKeyType, SlashCursor, SlashReason,
+    UnappliedSlashes, UnbondingUntil, ValidatedOperations, ValidationStatus, ValidatorStates, ValidatorStatus,
+    Votes, WeightInfo, INITIAL_REPUTATION,
+};
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{
+        fungible::{Inspect, InspectHold},
+        Hooks,
+    },
+    weights::Weight,
+};
+use codec::Encode;
+use sp_core::H256;
+use sp_runtime::Perbill;
+
+fn run_to_block(n: u64) {
+    while System::block_number() < n {
+        let next = System::block_number() + 1;
+        System::set_block_number(next);
+        QValidator::on_initialize(next);
+    }
+}
+
+fn register(who: u64) {
+    assert_ok!(QValidator::register_validator(
+        RuntimeOrigin::signed(who),
+        100,
+        QuantumKeyType::ECDSA,
+        vec![who as u8; 8]
+    ));
+}
+
+fn submit(who: u64, data: u8, expires_at: u64) -> H256 {
+    assert_ok!(QValidator::submit_operation(
+        RuntimeOrigin::signed(who),
+        0,
+        OperationType::Measurement,
+        vec![data],
+        expires_at,
+    ));
+    match System::events().last().map(|record| record.event.clone()) {
+        Some(RuntimeEvent::QValidator(Event::OperationSubmitted { operation_hash, .. })) => operation_hash,
+        _ => panic!("no OperationSubmitted event"),
+    }
+}
+
+fn vote(who: u64, operation_hash: H256, approve: bool) {
+    assert_ok!(QValidator::vote_validation(RuntimeOrigin::signed(who), operation_hash, approve));
+}
+
+fn held(who: u64) -> u64 {
+    Balances::balance_on_hold(&HoldReason::ValidatorStake.into(), &who)
+}
+
+fn status(who: u64) -> ValidatorStatus {
+    ValidatorStates::<Test>::get(who).unwrap().status
+}
+
+#[test]
+fn register_validator_works() {
+    new_test_ext().execute_with(|| {
+        register(1);
+
+        let state = ValidatorStates::<Test>::get(1).unwrap();
+        assert_eq!(state.stake, 100);
+        assert_eq!(state.status, ValidatorStatus::Active);
+        assert_eq!(state.key_type, QuantumKeyType::ECDSA);
+        assert_eq!(state.quantum_pubkey.into_inner(), vec![1; 8]);
+        assert_eq!(state.last_update, 1);
+        assert_eq!(held(1), 100);
+        assert_eq!(Balances::balance(&1), BALANCE - 100);
+        System::assert_last_event(Event::ValidatorRegistered { validator: 1 }.into());
+    });
+}
+
+#[test]
+fn register_validator_rejections() {
+    new_test_ext().execute_with(|| {
+        let origin = || RuntimeOrigin::signed(1);
+
+        assert_noop!(
+            QValidator::register_validator(origin(), MinValidatorStake::get() - 1, QuantumKeyType::ECDSA, vec![1]),
+            Error::<Test>::InsufficientStake
+        );
+        assert_noop!(
+            QValidator::register_validator(origin(), BALANCE + 1, QuantumKeyType::ECDSA, vec![1]),
+            Error::<Test>::InsufficientStake
+        );
+        assert_noop!(
+            QValidator::register_validator(
+                RuntimeOrigin::signed(POOR),
+                MinValidatorStake::get(),
+                QuantumKeyType::ECDSA,
+                vec![1]
+            ),
+            Error::<Test>::InsufficientStake
+        );
+        assert_noop!(
+            QValidator::register_validator(origin(), 100, QuantumKeyType::ECDSA, vec![]),
+            Error::<Test>::InvalidQuantumState
+        );
+        assert_noop!(
+            QValidator::register_validator(origin(), 100, QuantumKeyType::ECDSA, vec![0; 2049]),
+            Error::<Test>::QuantumStateTooLarge
+        );
+
+        register(1);
+        assert_noop!(
+            QValidator::register_validator(origin(), 100, QuantumKeyType::ECDSA, vec![1]),
+            Error::<Test>::ValidatorAlreadyRegistered
+        );
+    });
+}
+
+#[test]
+fn update_quantum_key_works() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        System::set_block_number(3);
+
+        assert_ok!(QValidator::update_quantum_key(RuntimeOrigin::signed(1), QuantumKeyType::ECDSA, vec![7; 32]));
+        let state = ValidatorStates::<Test>::get(1).unwrap();
+        assert_eq!(state.quantum_pubkey.into_inner(), vec![7; 32]);
+        assert_eq!(state.last_update, 3);
+        System::assert_last_event(Event::ValidatorUpdated { validator: 1 }.into());
+
+        assert_ok!(QValidator::go_offline(RuntimeOrigin::signed(1)));
+        assert_ok!(QValidator::update_quantum_key(RuntimeOrigin::signed(1), QuantumKeyType::ECDSA, vec![8]));
+    });
+}
+
+#[test]
+fn update_quantum_key_rejections() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            QValidator::update_quantum_key(RuntimeOrigin::signed(1), QuantumKeyType::ECDSA, vec![1]),
+            Error::<Test>::ValidatorNotFound
+        );
+
+        register(1);
+        assert_noop!(
+            QValidator::update_quantum_key(RuntimeOrigin::signed(1), QuantumKeyType::ECDSA, vec![]),
+            Error::<Test>::InvalidQuantumState
+        );
+        assert_noop!(
+            QValidator::update_quantum_key(RuntimeOrigin::signed(1), QuantumKeyType::ECDSA, vec![0; 2049]),
+            Error::<Test>::QuantumStateTooLarge
+        );
+
+        assert_ok!(QValidator::leave(RuntimeOrigin::signed(1)));
+        assert_noop!(
+            QValidator::update_quantum_key(RuntimeOrigin::signed(1), QuantumKeyType::ECDSA, vec![1]),
+            Error::<Test>::ValidatorNotActive
+        );
+    });
+}
+
+#[test]
+fn go_offline_and_online() {
+    new_test_ext().execute_with(|| {
+        register(1);
+
+        assert_ok!(QValidator::go_offline(RuntimeOrigin::signed(1)));
+        assert_eq!(status(1), ValidatorStatus::Offline);
+        System::assert_last_event(
+            Event::ValidatorStatusChanged { validator: 1, status: ValidatorStatus::Offline }.into(),
+        );
+        assert_noop!(QValidator::go_offline(RuntimeOrigin::signed(1)), Error::<Test>::ValidatorNotActive);
+
+        assert_ok!(QValidator::go_online(RuntimeOrigin::signed(1)));
+        assert_eq!(status(1), ValidatorStatus::Active);
+        assert_noop!(QValidator::go_online(RuntimeOrigin::signed(1)), Error::<Test>::InvalidStatusTransition);
+
+        assert_noop!(QValidator::go_offline(RuntimeOrigin::signed(2)), Error::<Test>::ValidatorNotFound);
+        assert_noop!(QValidator::go_online(RuntimeOrigin::signed(2)), Error::<Test>::ValidatorNotFound);
+    });
+}
+
+#[test]
+fn leave_works() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        assert_ok!(QValidator::go_offline(RuntimeOrigin::signed(2)));
+
+        assert_ok!(QValidator::leave(RuntimeOrigin::signed(1)));
+        assert_ok!(QValidator::leave(RuntimeOrigin::signed(2)));
+        assert_eq!(status(1), ValidatorStatus::Leaving);
+        assert_eq!(status(2), ValidatorStatus::Leaving);
+        System::assert_last_event(
+            Event::ValidatorStatusChanged { validator: 2, status: ValidatorStatus::Leaving }.into(),
+        );
+        assert_eq!(UnbondingUntil::<Test>::get(1), Some(1 + UnbondingPeriod::get()));
+    });
+}
+
+#[test]
+fn withdraw_stake_after_unbonding() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        assert_noop!(QValidator::withdraw_stake(RuntimeOrigin::signed(1)), Error::<Test>::NotLeaving);
+        assert_noop!(QValidator::withdraw_stake(RuntimeOrigin::signed(2)), Error::<Test>::ValidatorNotFound);
+
+        assert_ok!(QValidator::leave(RuntimeOrigin::signed(1)));
+        System::set_block_number(UnbondingPeriod::get());
+        assert_noop!(QValidator::withdraw_stake(RuntimeOrigin::signed(1)), Error::<Test>::StillUnbonding);
+        assert_eq!(held(1), 100);
+
+        System::set_block_number(1 + UnbondingPeriod::get());
+        assert_ok!(QValidator::withdraw_stake(RuntimeOrigin::signed(1)));
+        assert_eq!(held(1), 0);
+        assert_eq!(Balances::balance(&1), BALANCE);
+        assert!(ValidatorStates::<Test>::get(1).is_none());
+        assert!(UnbondingUntil::<Test>::get(1).is_none());
+        System::assert_last_event(Event::StakeWithdrawn { validator: 1, amount: 100 }.into());
+
+        // The account may register again once it has left
+        register(1);
+    });
+}
+
+#[test]
+fn leaving_validators_cannot_change_status() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(QValidator::leave(RuntimeOrigin::signed(1)), Error::<Test>::ValidatorNotFound);
+
+        register(1);
+        assert_ok!(QValidator::leave(RuntimeOrigin::signed(1)));
+
+        assert_noop!(QValidator::leave(RuntimeOrigin::signed(1)), Error::<Test>::InvalidStatusTransition);
+        assert_noop!(QValidator::go_offline(RuntimeOrigin::signed(1)), Error::<Test>::ValidatorNotActive);
+        assert_noop!(QValidator::go_online(RuntimeOrigin::signed(1)), Error::<Test>::InvalidStatusTransition);
+    });
+}
+
+#[test]
+fn validator_status_transitions() {
+    use ValidatorStatus::*;
+
+    assert!(Active.can_transition_to(Offline));
+    assert!(Offline.can_transition_to(Active));
+    assert!(Active.can_transition_to(Slashed));
+    assert!(Slashed.can_transition_to(Leaving));
+    assert!(!Slashed.can_transition_to(Active));
+    assert!(!Leaving.can_transition_to(Active));
+    assert!(!Leaving.can_transition_to(Leaving));
+    assert!(!Active.can_transition_to(Active));
+}
+
+#[test]
+fn submit_operation_works() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        let hash = submit(5, 0, 10);
+
+        let operation = PendingOperations::<Test>::get(hash).unwrap();
+        assert_eq!(operation.submitter, 5);
+        assert_eq!(operation.op_type, OperationType::Measurement);
+        assert_eq!(operation.submitted_at, 1);
+        assert_eq!(operation.expires_at, 10);
+        assert!(ValidatedOperations::<Test>::get(hash).is_none());
+    });
+}
+
+#[test]
+fn submit_operation_rejections() {
+    new_test_ext().execute_with(|| {
+        let origin = || RuntimeOrigin::signed(5);
+        let op_type = OperationType::Measurement;
+
+        register(1);
+        assert_noop!(
+            QValidator::submit_operation(origin(), 0, op_type, vec![0], 10),
+            Error::<Test>::NotEnoughValidators
+        );
+        register(2);
+
+        assert_noop!(
+            QValidator::submit_operation(origin(), 0, op_type, vec![0], 1),
+            Error::<Test>::OperationExpired
+        );
+        assert_noop!(
+            QValidator::submit_operation(origin(), 0, op_type, vec![], 10),
+            Error::<Test>::InvalidQuantumState
+        );
+        assert_noop!(
+            QValidator::submit_operation(origin(), 0, op_type, vec![0; 2049], 10),
+            Error::<Test>::QuantumStateTooLarge
+        );
+
+        submit(5, 0, 10);
+        assert_noop!(
+            QValidator::submit_operation(origin(), 0, op_type, vec![0], 10),
+            Error::<Test>::OperationAlreadyExists
+        );
+    });
+}
+
+#[test]
+fn quorum_of_approvals_validates_operation() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        register(3);
+        let hash = submit(5, 0, 10);
+
+        vote(1, hash, true);
+        vote(2, hash, false);
+        let result = ValidatedOperations::<Test>::get(hash).unwrap();
+        assert_eq!(result.status, ValidationStatus::InProgress);
+        assert_eq!(result.validators.clone().into_inner(), vec![1, 2]);
+        assert_eq!(result.approvals, 1);
+        assert!(PendingOperations::<Test>::contains_key(hash));
+
+        System::set_block_number(4);
+        vote(3, hash, true);
+        let result = ValidatedOperations::<Test>::get(hash).unwrap();
+        assert_eq!(result.status, ValidationStatus::Success);
+        assert_eq!(result.approvals, 2);
+        assert_eq!(result.completed_at, 4);
+        assert!(!PendingOperations::<Test>::contains_key(hash));
+        System::assert_last_event(
+            Event::OperationValidated { operation_hash: hash, status: ValidationStatus::Success }.into(),
+        );
+
+        // Finalized operations take no further votes
+        register(4);
+        assert_noop!(
+            QValidator::vote_validation(RuntimeOrigin::signed(4), hash, true),
+            Error::<Test>::OperationNotFound
+        );
+    });
+}
+
+#[test]
+fn quorum_of_rejections_fails_operation() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        let hash = submit(5, 0, 10);
+
+        vote(1, hash, false);
+        vote(2, hash, false);
+        let result = ValidatedOperations::<Test>::get(hash).unwrap();
+        assert_eq!(result.status, ValidationStatus::Failed);
+        assert_eq!(result.approvals, 0);
+        assert!(!PendingOperations::<Test>::contains_key(hash));
+        System::assert_last_event(
+            Event::OperationValidated { operation_hash: hash, status: ValidationStatus::Failed }.into(),
+        );
+    });
+}
+
+#[test]
+fn vote_validation_rejections() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        let hash = submit(5, 0, 3);
+        assert_noop!(
+            QValidator::vote_validation(RuntimeOrigin::signed(3), hash, true),
+            Error::<Test>::ValidatorNotFound
+        );
+
+        assert_ok!(QValidator::go_offline(RuntimeOrigin::signed(2)));
+        assert_noop!(
+            QValidator::vote_validation(RuntimeOrigin::signed(2), hash, true),
+            Error::<Test>::ValidatorNotActive
+        );
+        assert_noop!(
+            QValidator::vote_validation(RuntimeOrigin::signed(1), H256::zero(), true),
+            Error::<Test>::OperationNotFound
+        );
+
+        register(3);
+        assert_noop!(
+            QValidator::vote_validation(RuntimeOrigin::signed(3), hash, true),
+            Error::<Test>::NotAssigned
+        );
+
+        vote(1, hash, true);
+        assert_noop!(
+            QValidator::vote_validation(RuntimeOrigin::signed(1), hash, true),
+            Error::<Test>::AlreadyVoted
+        );
+
+        assert_ok!(QValidator::go_online(RuntimeOrigin::signed(2)));
+        System::set_block_number(3);
+        assert_noop!(
+            QValidator::vote_validation(RuntimeOrigin::signed(2), hash, true),
+            Error::<Test>::OperationExpired
+        );
+    });
+}
+
+#[test]
+fn votes_against_quorum_are_slashed_after_deferral() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        register(3);
+        let hash = submit(5, 0, 10);
+
+        vote(1, hash, true);
+        vote(2, hash, false);
+        vote(3, hash, true);
+        assert!(Votes::<Test>::iter_prefix(hash).next().is_none());
+
+        let apply_at = 1 + SlashDeferDuration::get();
+        let slash = UnappliedSlashes::<Test>::get(apply_at, 0).unwrap();
+        assert_eq!((slash.validator, slash.reason, slash.operation_hash), (2, SlashReason::WrongVote, hash));
+        assert!(System::events().iter().any(|record| record.event
+            == Event::SlashReported { slash_id: 0, validator: 2, reason: SlashReason::WrongVote, apply_at }.into()));
+
+        run_to_block(apply_at - 1);
+        assert_eq!(held(2), 100);
+
+        run_to_block(apply_at);
+        assert_eq!(held(2), 90);
+        assert_eq!(ValidatorStates::<Test>::get(2).unwrap().stake, 90);
+        assert_eq!(status(2), ValidatorStatus::Active);
+        assert_eq!(Balances::balance(&TREASURY), 10);
+        assert_eq!(held(1), 100);
+        System::assert_last_event(Event::Slashed { validator: 2, reason: SlashReason::WrongVote, amount: 10 }.into());
+    });
+}
+
+#[test]
+fn equivocation_is_slashed() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        let hash = submit(5, 0, 10);
+
+        vote(1, hash, true);
+        vote(1, hash, false);
+        let result = ValidatedOperations::<Test>::get(hash).unwrap();
+        assert_eq!(result.validators.into_inner(), vec![1]);
+        assert_eq!(result.approvals, 1);
+        System::assert_last_event(
+            Event::SlashReported {
+                slash_id: 0,
+                validator: 1,
+                reason: SlashReason::Equivocation,
+                apply_at: 1 + SlashDeferDuration::get(),
+            }
+            .into(),
+        );
+
+        // Further conflicting votes are not slashed again
+        assert_noop!(
+            QValidator::vote_validation(RuntimeOrigin::signed(1), hash, true),
+            Error::<Test>::AlreadyVoted
+        );
+        assert_eq!(PendingSlashes::<Test>::get(1), 1);
+
+        assert!(Equivocations::<Test>::contains_key(hash, 1));
+        vote(2, hash, true);
+        assert!(!Equivocations::<Test>::contains_key(hash, 1));
+        assert_eq!(PendingSlashes::<Test>::get(1), 1);
+
+        run_to_block(1 + SlashDeferDuration::get());
+        assert_eq!(held(1), 50);
+        assert_eq!(PendingSlashes::<Test>::get(1), 0);
+        assert_eq!(status(1), ValidatorStatus::Slashed);
+        assert_noop!(
+            QValidator::vote_validation(RuntimeOrigin::signed(1), hash, true),
+            Error::<Test>::ValidatorNotActive
+        );
+    });
+}
+
+#[test]
+fn missed_votes_are_slashed() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        assert_ok!(QValidator::register_validator(
+            RuntimeOrigin::signed(2),
+            MinValidatorStake::get(),
+            QuantumKeyType::ECDSA,
+            vec![2]
+        ));
+        let hash = submit(5, 0, 10);
+
+        QValidator::report_missed_votes(hash, vec![1, 2]);
+        run_to_block(1 + SlashDeferDuration::get());
+
+        assert_eq!(held(1), 95);
+        assert_eq!(status(1), ValidatorStatus::Active);
+
+        // Left with less than the minimum stake
+        let stake = ValidatorStates::<Test>::get(2).unwrap().stake;
+        assert!(stake < MinValidatorStake::get());
+        assert_eq!(held(2), stake);
+        assert_eq!(status(2), ValidatorStatus::Slashed);
+        assert_eq!(Balances::balance(&TREASURY), 5 + MinValidatorStake::get() - stake);
+    });
+}
+
+#[test]
+fn slashes_apply_to_unbonding_validators() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        submit(5, 0, 3);
+        assert_ok!(QValidator::leave(RuntimeOrigin::signed(1)));
+
+        // Expiry reports the missed vote, applied before unbonding ends
+        run_to_block(1 + UnbondingPeriod::get());
+        assert_ok!(QValidator::withdraw_stake(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::StakeWithdrawn { validator: 1, amount: 95 }.into());
+        assert_eq!(Balances::balance(&1), BALANCE - 5);
+    });
+}
+
+#[test]
+fn withdraw_waits_for_assignments_and_slashes() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        let hash = submit(5, 0, 10);
+        assert_ok!(QValidator::leave(RuntimeOrigin::signed(1)));
+
+        // Expires after the unbonding period
+        run_to_block(1 + UnbondingPeriod::get());
+        assert_noop!(QValidator::withdraw_stake(RuntimeOrigin::signed(1)), Error::<Test>::StillAssigned);
+
+        run_to_block(10);
+        assert!(!Assignments::<Test>::contains_key(1, hash));
+        assert_eq!(PendingSlashes::<Test>::get(1), 1);
+        assert_noop!(QValidator::withdraw_stake(RuntimeOrigin::signed(1)), Error::<Test>::SlashesPending);
+
+        run_to_block(10 + SlashDeferDuration::get());
+        assert_ok!(QValidator::withdraw_stake(RuntimeOrigin::signed(1)));
+        System::assert_last_event(Event::StakeWithdrawn { validator: 1, amount: 95 }.into());
+        assert_eq!(Assignments::<Test>::iter_key_prefix(1).count(), 0);
+        assert!(!PendingSlashes::<Test>::contains_key(1));
+    });
+}
+
+#[test]
+fn cancel_slash_works() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        let hash = submit(5, 0, 10);
+        QValidator::report_missed_votes(hash, vec![1, 1]);
+        let apply_at = 1 + SlashDeferDuration::get();
+
+        assert_noop!(
+            QValidator::cancel_slash(RuntimeOrigin::signed(1), apply_at, 0),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_eq!(PendingSlashes::<Test>::get(1), 2);
+        assert_ok!(QValidator::cancel_slash(RuntimeOrigin::root(), apply_at, 0));
+        System::assert_last_event(Event::SlashCancelled { slash_id: 0 }.into());
+        assert_eq!(PendingSlashes::<Test>::get(1), 1);
+        assert_noop!(
+            QValidator::cancel_slash(RuntimeOrigin::root(), apply_at, 0),
+            Error::<Test>::SlashNotFound
+        );
+
+        run_to_block(apply_at);
+        assert_eq!(held(1), 95);
+        // The deferral window has passed
+        assert_noop!(
+            QValidator::cancel_slash(RuntimeOrigin::root(), apply_at, 1),
+            Error::<Test>::SlashNotFound
+        );
+    });
+}
+
+#[test]
+fn pending_operations_expire() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        let hash = submit(5, 0, 3);
+        vote(1, hash, true);
+        assert!(ExpiryQueue::<Test>::contains_key(3, hash));
+
+        run_to_block(2);
+        assert!(PendingOperations::<Test>::contains_key(hash));
+
+        run_to_block(3);
+        assert!(!PendingOperations::<Test>::contains_key(hash));
+        assert!(!ExpiryQueue::<Test>::contains_key(3, hash));
+        assert!(Votes::<Test>::get(hash, 1).is_none());
+        let result = ValidatedOperations::<Test>::get(hash).unwrap();
+        assert_eq!(result.status, ValidationStatus::Expired);
+        assert_eq!(result.validators.into_inner(), vec![1]);
+        assert_eq!(result.completed_at, 3);
+        System::assert_last_event(
+            Event::OperationValidated { operation_hash: hash, status: ValidationStatus::Expired }.into(),
+        );
+    });
+}
+
+#[test]
+fn validated_operations_do_not_expire() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        let hash = submit(5, 0, 3);
+        vote(1, hash, true);
+        vote(2, hash, true);
+
+        run_to_block(3 + RetentionPeriod::get());
+        assert_eq!(ValidatedOperations::<Test>::get(hash).unwrap().status, ValidationStatus::Success);
+    });
+}
+
+#[test]
+fn lagging_expiry_cursor_is_bounded() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(ExpiryCursor::<Test>::get(), Some(0));
+
+        // No cursor, as when the pallet is added to a live chain
+        ExpiryCursor::<Test>::kill();
+        run_to_block(2);
+        assert_eq!(ExpiryCursor::<Test>::get(), Some(3));
+
+        // Budget for ten queue reads beyond the cursor itself
+        MaxExpiryWeight::set(&TestDbWeight::get().reads_writes(11, 1));
+        ExpiryCursor::<Test>::put(0);
+        System::set_block_number(1_000_000);
+        QValidator::on_initialize(1_000_000);
+        assert_eq!(ExpiryCursor::<Test>::get(), Some(10));
+    });
+}
+
+#[test]
+fn expiry_respects_weight_budget() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        let hashes = [submit(5, 0, 3), submit(5, 1, 3), submit(5, 2, 3)];
+
+        run_to_block(3);
+        let expired = |hash| !PendingOperations::<Test>::contains_key(hash);
+        assert_eq!(hashes.iter().filter(|hash| expired(**hash)).count(), 2);
+        assert_eq!(ExpiryCursor::<Test>::get(), Some(3));
+
+        run_to_block(4);
+        assert!(hashes.iter().all(|hash| expired(*hash)));
+        assert_eq!(ExpiryCursor::<Test>::get(), Some(5));
+        for hash in hashes {
+            assert_eq!(ValidatedOperations::<Test>::get(hash).unwrap().status, ValidationStatus::Expired);
+        }
+    });
+}
+
+#[test]
+fn slashes_respect_weight_budget() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        register(3);
+        assert_eq!(SlashCursor::<Test>::get(), Some(0));
+        MaxSlashWeight::set(&<() as WeightInfo>::apply_slashes(1).saturating_add(Weight::from_parts(100, 0)));
+        let hash = submit(5, 0, 10);
+        QValidator::report_missed_votes(hash, vec![1, 2, 3]);
+
+        run_to_block(3);
+        assert_eq!(UnappliedSlashes::<Test>::iter_prefix(3).count(), 2);
+        assert_eq!(SlashCursor::<Test>::get(), Some(3));
+
+        // Carried over slashes can still be cancelled
+        let (slash_id, _) = UnappliedSlashes::<Test>::iter_prefix(3).next().unwrap();
+        assert_ok!(QValidator::cancel_slash(RuntimeOrigin::root(), 3, slash_id));
+
+        run_to_block(4);
+        assert_eq!(UnappliedSlashes::<Test>::iter().count(), 0);
+        assert_eq!(SlashCursor::<Test>::get(), Some(5));
+        let mut stakes = [held(1), held(2), held(3)];
+        stakes.sort();
+        assert_eq!(stakes, [95, 95, 100]);
+    });
+}
+
+#[test]
+fn expired_results_are_pruned() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        let hash = submit(5, 0, 3);
+
+        run_to_block(3 + RetentionPeriod::get() - 1);
+        assert!(ValidatedOperations::<Test>::contains_key(hash));
+
+        run_to_block(3 + RetentionPeriod::get());
+        assert!(!ValidatedOperations::<Test>::contains_key(hash));
+    });
+}
+
+#[test]
+fn finalized_votes_update_metrics() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        register(3);
+        let hash = submit(5, 0, 10);
+
+        System::set_block_number(2);
+        vote(1, hash, true);
+        vote(2, hash, false);
+        System::set_block_number(4);
+        vote(3, hash, true);
+
+        let metrics = |who| ValidatorStates::<Test>::get(who).unwrap().metrics;
+        assert_eq!(metrics(1).operations_validated, 1);
+        assert_eq!(metrics(1).successful_validations, 1);
+        assert_eq!(metrics(1).avg_validation_blocks(), 1);
+        assert_eq!(metrics(2).failed_validations, 1);
+        assert_eq!(metrics(2).successful_validations, 0);
+        assert_eq!(metrics(3).avg_validation_blocks(), 3);
+
+        let second = submit(5, 1, 10);
+        vote(1, second, true);
+        vote(3, second, true);
+        assert_eq!(metrics(1).operations_validated, 2);
+        assert_eq!(metrics(1).avg_validation_blocks(), 0);
+        assert_eq!(metrics(3).avg_validation_blocks(), 1);
+
+        // Latencies of 1, 0 and 2 blocks average to exactly 1
+        let third = submit(5, 2, 10);
+        System::set_block_number(6);
+        vote(1, third, true);
+        vote(3, third, true);
+        assert_eq!(metrics(1).total_validation_blocks, 3);
+        assert_eq!(metrics(1).avg_validation_blocks(), 1);
+        assert_eq!(metrics(3).total_validation_blocks, 5);
+    });
+}
+
+#[test]
+fn unfinalized_votes_do_not_update_metrics() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        let hash = submit(5, 0, 3);
+        vote(1, hash, true);
+
+        run_to_block(3);
+        assert_eq!(ValidatorStates::<Test>::get(1).unwrap().metrics.operations_validated, 0);
+        assert_eq!(QValidator::reputation(&1), Some(INITIAL_REPUTATION * Perbill::from_percent(25)));
+    });
+}
+
+#[test]
+fn reputation_follows_votes_and_decays() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        register(3);
+        assert_eq!(QValidator::reputation(&1), Some(INITIAL_REPUTATION));
+        assert_eq!(QValidator::reputation(&4), None);
+
+        let hash = submit(5, 0, 10);
+        vote(1, hash, true);
+        vote(2, hash, false);
+        vote(3, hash, true);
+        assert_eq!(QValidator::reputation(&1), Some(Perbill::from_percent(75)));
+        assert_eq!(QValidator::reputation(&2), Some(Perbill::from_percent(25)));
+
+        System::set_block_number(3);
+        assert_eq!(QValidator::reputation(&1), Some(Perbill::from_parts(187_500_000)));
+        assert_eq!(QValidator::reputation(&2), Some(Perbill::from_parts(62_500_000)));
+    });
+}
+
+#[test]
+fn active_validators_follow_status() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        register(3);
+        assert_eq!(ActiveValidators::<Test>::get().into_inner(), vec![1, 2, 3]);
+
+        assert_ok!(QValidator::go_offline(RuntimeOrigin::signed(2)));
+        assert_ok!(QValidator::leave(RuntimeOrigin::signed(3)));
+        assert_eq!(ActiveValidators::<Test>::get().into_inner(), vec![1]);
+
+        assert_ok!(QValidator::go_online(RuntimeOrigin::signed(2)));
+        assert_eq!(ActiveValidators::<Test>::get().into_inner(), vec![1, 2]);
+
+        for who in 4..=17 {
+            register(who);
+        }
+        assert_noop!(
+            QValidator::register_validator(RuntimeOrigin::signed(18), 100, QuantumKeyType::ECDSA, vec![18]),
+            Error::<Test>::TooManyValidators
+        );
+    });
+}
+
+#[test]
+fn committees_are_sampled_from_active_validators() {
+    new_test_ext().execute_with(|| {
+        for who in 1..=13 {
+            register(who);
+        }
+        assert_ok!(QValidator::go_offline(RuntimeOrigin::signed(13)));
+
+        let first = submit(20, 0, 10);
+        let second = submit(20, 1, 10);
+        for hash in [first, second] {
+            let mut committee = QValidator::committee(&hash);
+            assert_eq!(committee.len(), 10);
+            assert!(!committee.contains(&13));
+            committee.sort();
+            committee.dedup();
+            assert_eq!(committee.len(), 10);
+            for member in committee {
+                assert!(QValidator::assignments(&member).contains(&hash));
+            }
+        }
+        assert_ne!(QValidator::committee(&first), QValidator::committee(&second));
+
+        let outsider = (1..=12).find(|who| !QValidator::committee(&first).contains(who)).unwrap();
+        assert_noop!(
+            QValidator::vote_validation(RuntimeOrigin::signed(outsider), first, true),
+            Error::<Test>::NotAssigned
+        );
+    });
+}
+
+#[test]
+fn committee_is_cleared_on_finalization() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        register(3);
+        let hash = submit(5, 0, 10);
+        assert_eq!(Committees::<Test>::get(hash).unwrap().len(), 3);
+
+        vote(1, hash, true);
+        vote(2, hash, true);
+        assert!(Committees::<Test>::get(hash).is_none());
+        assert!(QValidator::committee(&hash).is_empty());
+        assert!(Assignments::<Test>::get(3, hash).is_none());
+        assert!(QValidator::assignments(&3).is_empty());
+    });
+}
+
+#[test]
+fn committee_members_missing_expired_votes_are_slashed() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        register(3);
+        let hash = submit(5, 0, 3);
+        vote(1, hash, true);
+
+        run_to_block(3);
+        assert!(QValidator::assignments(&2).is_empty());
+        let missed = UnappliedSlashes::<Test>::iter_prefix(3 + SlashDeferDuration::get())
+            .map(|(_, slash)| (slash.validator, slash.reason))
+            .collect::<Vec<_>>();
+        assert_eq!(missed.len(), 2);
+        assert!(missed.contains(&(2, SlashReason::MissedVote)));
+        assert!(missed.contains(&(3, SlashReason::MissedVote)));
+
+        run_to_block(3 + SlashDeferDuration::get());
+        assert_eq!(held(1), 100);
+        assert_eq!(held(2), 95);
+        assert_eq!(held(3), 95);
+    });
+}
+
+#[test]
+fn slashed_validators_leave_active_set() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        let hash = submit(5, 0, 10);
+        vote(1, hash, true);
+        vote(1, hash, false);
+
+        run_to_block(1 + SlashDeferDuration::get());
+        assert_eq!(ActiveValidators::<Test>::get().into_inner(), vec![2]);
+        assert_noop!(
+            QValidator::submit_operation(RuntimeOrigin::signed(5), 0, OperationType::Measurement, vec![1], 10),
+            Error::<Test>::NotEnoughValidators
+        );
+    });
+}
+
+#[test]
+fn state_queries_work() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        register(2);
+        register(3);
+        assert_ok!(QValidator::go_offline(RuntimeOrigin::signed(3)));
+
+        let info = QValidator::validator_info(&1).unwrap();
+        assert_eq!(info.stake, 100);
+        assert_eq!(info.status, ValidatorStatus::Active);
+        assert_eq!(info.key_type, QuantumKeyType::ECDSA);
+        assert_eq!(info.quantum_pubkey, vec![1; 8]);
+        assert_eq!(info.reputation, INITIAL_REPUTATION);
+        assert!(QValidator::validator_info(&9).is_none());
+
+        assert_eq!(QValidator::active_validators(0, 2), vec![1, 2]);
+        assert_eq!(QValidator::active_validators(1, 10), vec![2]);
+        assert!(QValidator::active_validators(5, 1).is_empty());
+
+        let hash = submit(5, 0, 10);
+        assert_eq!(QValidator::operation_status(&hash), Some(ValidationStatus::InProgress));
+        assert_eq!(QValidator::operation_status(&H256::zero()), None);
+
+        let aggregate = QValidator::aggregate_metrics();
+        assert_eq!(aggregate.registered_validators, 3);
+        assert_eq!(aggregate.active_validators, 2);
+        assert_eq!(aggregate.active_stake, 200);
+        assert_eq!(aggregate.pending_operations, 1);
+        assert_eq!(aggregate.operations_validated, 0);
+
+        System::set_block_number(3);
+        vote(1, hash, true);
+        vote(2, hash, true);
+        assert_eq!(QValidator::operation_status(&hash), Some(ValidationStatus::Success));
+        assert_eq!(QValidator::operations_validated(), vec![(hash, ValidationStatus::Success)]);
+
+        let aggregate = QValidator::aggregate_metrics();
+        assert_eq!(aggregate.pending_operations, 0);
+        assert_eq!(aggregate.operations_validated, 2);
+        assert_eq!(aggregate.successful_validations, 2);
+        assert_eq!(aggregate.avg_validation_blocks, 2);
+    });
+}
+
+#[test]
+fn genesis_registers_validators() {
+    let validators = vec![
+        (1, 100, QuantumKeyType::ECDSA, vec![1; 8], ValidatorStatus::Active),
+        (2, MinValidatorStake::get(), QuantumKeyType::ECDSA, vec![2; 32], ValidatorStatus::Offline),
+        (3, 200, QuantumKeyType::ECDSA, vec![3], ValidatorStatus::Active),
+    ];
+    new_test_ext_with_validators(validators).execute_with(|| {
+        assert_eq!(ActiveValidators::<Test>::get().into_inner(), vec![1, 3]);
+        assert_eq!(status(2), ValidatorStatus::Offline);
+        assert_eq!(held(1), 100);
+        assert_eq!(held(2), MinValidatorStake::get());
+        assert_eq!(Balances::balance(&3), BALANCE - 200);
+
+        let state = ValidatorStates::<Test>::get(1).unwrap();
+        assert_eq!(state.key_type, QuantumKeyType::ECDSA);
+        assert_eq!(state.quantum_pubkey.into_inner(), vec![1; 8]);
+        assert_eq!(state.last_update, 0);
+        assert_eq!(QValidator::reputation(&1), Some(INITIAL_REPUTATION * Perbill::from_percent(50)));
+
+        let hash = submit(5, 0, 10);
+        vote(1, hash, true);
+        vote(3, hash, true);
+        assert_eq!(QValidator::operation_status(&hash), Some(ValidationStatus::Success));
+    });
+}
+
+#[test]
+#[should_panic(expected = "Genesis validator stake below MinValidatorStake")]
+fn genesis_rejects_low_stake() {
+    new_test_ext_with_validators(vec![(
+        1,
+        MinValidatorStake::get() - 1,
+        QuantumKeyType::ECDSA,
+        vec![1],
+        ValidatorStatus::Active,
+    )]);
+}
+
+#[test]
+#[should_panic(expected = "Genesis validator quantum key must be non-empty, well-formed and within MaxQuantumStateSize")]
+fn genesis_rejects_oversized_key() {
+    new_test_ext_with_validators(vec![(1, 100, QuantumKeyType::ECDSA, vec![1; 2049], ValidatorStatus::Active)]);
+}
+
+#[test]
+#[should_panic(expected = "Genesis validator quantum key must be non-empty, well-formed and within MaxQuantumStateSize")]
+fn genesis_rejects_empty_key() {
+    new_test_ext_with_validators(vec![(1, 100, QuantumKeyType::ECDSA, vec![], ValidatorStatus::Active)]);
+}
+
+#[test]
+#[should_panic(expected = "Genesis validator quantum key must be non-empty, well-formed and within MaxQuantumStateSize")]
+fn genesis_rejects_malformed_hybrid_key() {
+    new_test_ext_with_validators(vec![(1, 100, QuantumKeyType::Hybrid, vec![1; 32], ValidatorStatus::Active)]);
+}
+
+#[test]
+#[should_panic(expected = "Genesis validator must be Active or Offline")]
+fn genesis_rejects_final_status() {
+    new_test_ext_with_validators(vec![(1, 100, QuantumKeyType::ECDSA, vec![1], ValidatorStatus::Slashed)]);
+}
+
+#[test]
+#[should_panic(expected = "Genesis validator registered twice")]
+fn genesis_rejects_duplicates() {
+    new_test_ext_with_validators(vec![
+        (1, 100, QuantumKeyType::ECDSA, vec![1], ValidatorStatus::Active),
+        (1, 100, QuantumKeyType::ECDSA, vec![2], ValidatorStatus::Offline),
+    ]);
+}
+
+#[test]
+fn staked_keys_follow_active_stake() {
+    new_test_ext().execute_with(|| {
+        register(1);
+        assert_ok!(QValidator::register_validator(RuntimeOrigin::signed(2), 300, QuantumKeyType::ECDSA, vec![2; 4]));
+        register(3);
+        assert_ok!(QValidator::go_offline(RuntimeOrigin::signed(3)));
+
+        assert_eq!(
+            QValidator::staked_keys(),
+            vec![(1, QuantumKeyType::ECDSA, vec![1; 8], 100), (2, QuantumKeyType::ECDSA, vec![2; 4], 300)]
+        );
+    });
+}
+
+#[test]
+fn hybrid_keys_decode_from_quantum_keys() {
+    let key = HybridPublicKey { ed25519: [1; 32], dilithium: vec![2; 1952] };
+    let encoded = key.encode();
+    assert_eq!(encoded.len(), 1986);
+    assert_eq!(HybridPublicKey::from_quantum_pubkey(&encoded), Some(key.clone()));
+
+    let mut trailing = encoded;
+    trailing.push(0);
+    assert_eq!(HybridPublicKey::from_quantum_pubkey(&trailing), None);
+    let unknown_length = HybridPublicKey { dilithium: vec![2; 1951], ..key };
+    assert_eq!(HybridPublicKey::from_quantum_pubkey(&unknown_length.encode()), None);
+    assert_eq!(HybridPublicKey::from_quantum_pubkey(&[1; 32]), None);
+}
+
+#[test]
+fn hybrid_keys_register_and_update() {
+    new_test_ext().execute_with(|| {
+        let key = HybridPublicKey { ed25519: [1; 32], dilithium: vec![2; 1952] };
+        assert_ok!(QValidator::register_validator(RuntimeOrigin::signed(1), 100, QuantumKeyType::Hybrid, key.encode()));
+        assert_eq!(QValidator::hybrid_key(&1), Some(key.clone()));
+        assert_eq!(QValidator::staked_keys(), vec![(1, QuantumKeyType::Hybrid, key.encode(), 100)]);
+
+        register(2);
+        assert_eq!(QValidator::hybrid_key(&2), None);
+        let rotated = HybridPublicKey { ed25519: [3; 32], ..key.clone() };
+        assert_ok!(QValidator::update_quantum_key(RuntimeOrigin::signed(2), QuantumKeyType::Hybrid, rotated.encode()));
+        assert_eq!(QValidator::hybrid_key(&2), Some(rotated.clone()));
+        assert_eq!(QValidator::hybrid_key(&3), None);
+
+        // Moving back to a classical key drops the hybrid key
+        assert_ok!(QValidator::update_quantum_key(RuntimeOrigin::signed(2), QuantumKeyType::ECDSA, vec![2; 32]));
+        assert_eq!(QValidator::hybrid_key(&2), None);
+        assert_eq!(ValidatorStates::<Test>::get(2).unwrap().key_type, QuantumKeyType::ECDSA);
+    });
+}
+
+#[test]
+fn malformed_hybrid_keys_are_rejected() {
+    new_test_ext().execute_with(|| {
+        let mut truncated = HybridPublicKey { ed25519: [1; 32], dilithium: vec![2; 1952] }.encode();
+        truncated.pop();
+        let mut trailing = HybridPublicKey { ed25519: [1; 32], dilithium: vec![2; 1952] }.encode();
+        trailing.push(0);
+
+        for key in [truncated, trailing, vec![1; 32]] {
+            assert_noop!(
+                QValidator::register_validator(RuntimeOrigin::signed(1), 100, QuantumKeyType::Hybrid, key.clone()),
+                Error::<Test>::InvalidHybridKey
+            );
+        }
+
+        register(1);
+        assert_noop!(
+            QValidator::update_quantum_key(RuntimeOrigin::signed(1), QuantumKeyType::Hybrid, vec![1; 32]),
+            Error::<Test>::InvalidHybridKey
+        );
+        assert_eq!(QValidator::hybrid_key(&1), None);
+    });
+}
+
+#[test]
+fn key_types_are_stored_not_inferred() {
+    new_test_ext().execute_with(|| {
+        // An opaque key that decodes as a hybrid key stays opaque
+        let key = HybridPublicKey { ed25519: [1; 32], dilithium: vec![2; 1952] }.encode();
+        assert_ok!(QValidator::register_validator(RuntimeOrigin::signed(1), 100, QuantumKeyType::ECDSA, key.clone()));
+        assert_eq!(QValidator::hybrid_key(&1), None);
+        assert_eq!(QValidator::staked_keys(), vec![(1, QuantumKeyType::ECDSA, key, 100)]);
+
+        // Keys of other types are not parsed as hybrid keys, whatever
+        // their bytes
+        let mut header_only = vec![1; 32];
+        header_only.extend_from_slice(&HybridPublicKey { ed25519: [1; 32], dilithium: vec![2; 1952] }.encode()[32..34]);
+        assert_ok!(QValidator::register_validator(
+            RuntimeOrigin::signed(2),
+            100,
+            QuantumKeyType::HashBased,
+            header_only.clone()
+        ));
+        assert_eq!(QValidator::validator_info(&2).unwrap().key_type, QuantumKeyType::HashBased);
+        assert_eq!(QValidator::validator_info(&2).unwrap().quantum_pubkey, header_only);
+    });
+}
@@ -0,0 +1,209 @@
+//! Weights for quantum_validator
+//!
+//! PLACEHOLDER: hand-written estimates, not benchmark results. They have
+//! not been measured on any hardware and must be regenerated before use
+//! in production. Until then `SubstrateWeight` is deprecated, so a runtime
+//! using it gets a warning. Regenerate with:
+//!
+//! ./target/release/imrt-node benchmark pallet --chain=dev --steps=50 --repeat=20
+//!     --pallet=quantum_validator --extrinsic='*' --wasm-execution=compiled
+//!     --output=pallets/qvalidator/src/weights.rs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for quantum_validator.
+pub trait WeightInfo {
+    fn register_validator(k: u32, v: u32, ) -> Weight;
+    fn update_quantum_key(k: u32, ) -> Weight;
+    fn go_offline(v: u32, ) -> Weight;
+    fn go_online(v: u32, ) -> Weight;
+    fn leave(v: u32, ) -> Weight;
+    fn submit_operation(d: u32, v: u32, ) -> Weight;
+    fn vote_validation() -> Weight;
+    fn withdraw_stake() -> Weight;
+    fn cancel_slash() -> Weight;
+    fn apply_slashes(s: u32, ) -> Weight;
+    fn expire_operation() -> Weight;
+    fn prune_expired(p: u32, ) -> Weight;
+}
+
+/// Placeholder weights for quantum_validator, see the module documentation.
+#[deprecated(note = "placeholder weights; regenerate them from the benchmarks before use")]
+pub struct SubstrateWeight<T>(PhantomData<T>);
+#[allow(deprecated)]
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    fn register_validator(k: u32, v: u32, ) -> Weight {
+        Weight::from_parts(48_000_000, 4_800)
+            .saturating_add(Weight::from_parts(2_000, 0).saturating_mul(k.into()))
+            .saturating_add(Weight::from_parts(65_000, 0).saturating_mul(v.into()))
+            .saturating_add(Weight::from_parts(0, 32).saturating_mul(v.into()))
+            .saturating_add(T::DbWeight::get().reads(4_u64))
+            .saturating_add(T::DbWeight::get().writes(4_u64))
+    }
+    fn update_quantum_key(k: u32, ) -> Weight {
+        Weight::from_parts(18_000_000, 3_700)
+            .saturating_add(Weight::from_parts(1_500, 0).saturating_mul(k.into()))
+            .saturating_add(T::DbWeight::get().reads(1_u64))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+    }
+    fn go_offline(v: u32, ) -> Weight {
+        Weight::from_parts(22_000_000, 3_700)
+            .saturating_add(Weight::from_parts(55_000, 0).saturating_mul(v.into()))
+            .saturating_add(Weight::from_parts(0, 32).saturating_mul(v.into()))
+            .saturating_add(T::DbWeight::get().reads(2_u64))
+            .saturating_add(T::DbWeight::get().writes(2_u64))
+    }
+    fn go_online(v: u32, ) -> Weight {
+        Weight::from_parts(22_000_000, 3_700)
+            .saturating_add(Weight::from_parts(50_000, 0).saturating_mul(v.into()))
+            .saturating_add(Weight::from_parts(0, 32).saturating_mul(v.into()))
+            .saturating_add(T::DbWeight::get().reads(2_u64))
+            .saturating_add(T::DbWeight::get().writes(2_u64))
+    }
+    fn leave(v: u32, ) -> Weight {
+        Weight::from_parts(24_000_000, 3_700)
+            .saturating_add(Weight::from_parts(55_000, 0).saturating_mul(v.into()))
+            .saturating_add(Weight::from_parts(0, 32).saturating_mul(v.into()))
+            .saturating_add(T::DbWeight::get().reads(2_u64))
+            .saturating_add(T::DbWeight::get().writes(3_u64))
+    }
+    fn submit_operation(d: u32, v: u32, ) -> Weight {
+        Weight::from_parts(62_000_000, 5_200)
+            .saturating_add(Weight::from_parts(2_000, 0).saturating_mul(d.into()))
+            .saturating_add(Weight::from_parts(9_500_000, 0).saturating_mul(v.into()))
+            .saturating_add(Weight::from_parts(0, 2_700).saturating_mul(v.into()))
+            .saturating_add(T::DbWeight::get().reads(4_u64))
+            .saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(v.into())))
+            .saturating_add(T::DbWeight::get().writes(13_u64))
+    }
+    fn vote_validation() -> Weight {
+        Weight::from_parts(185_000_000, 27_000)
+            .saturating_add(T::DbWeight::get().reads(25_u64))
+            .saturating_add(T::DbWeight::get().writes(43_u64))
+    }
+    fn withdraw_stake() -> Weight {
+        Weight::from_parts(46_000_000, 4_800)
+            .saturating_add(T::DbWeight::get().reads(4_u64))
+            .saturating_add(T::DbWeight::get().writes(4_u64))
+    }
+    fn cancel_slash() -> Weight {
+        Weight::from_parts(16_000_000, 3_600)
+            .saturating_add(T::DbWeight::get().reads(1_u64))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+    }
+    fn apply_slashes(s: u32, ) -> Weight {
+        Weight::from_parts(9_000_000, 3_500)
+            .saturating_add(Weight::from_parts(72_000_000, 0).saturating_mul(s.into()))
+            .saturating_add(Weight::from_parts(0, 7_400).saturating_mul(s.into()))
+            .saturating_add(T::DbWeight::get().reads(3_u64))
+            .saturating_add(T::DbWeight::get().reads((6_u64).saturating_mul(s.into())))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+            .saturating_add(T::DbWeight::get().writes((6_u64).saturating_mul(s.into())))
+    }
+    fn expire_operation() -> Weight {
+        Weight::from_parts(128_000_000, 9_400)
+            .saturating_add(T::DbWeight::get().reads(8_u64))
+            .saturating_add(T::DbWeight::get().writes(37_u64))
+    }
+    fn prune_expired(p: u32, ) -> Weight {
+        Weight::from_parts(8_000_000, 3_500)
+            .saturating_add(Weight::from_parts(6_200_000, 0).saturating_mul(p.into()))
+            .saturating_add(Weight::from_parts(0, 2_500).saturating_mul(p.into()))
+            .saturating_add(T::DbWeight::get().reads(3_u64))
+            .saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(p.into())))
+            .saturating_add(T::DbWeight::get().writes(1_u64))
+            .saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(p.into())))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn register_validator(k: u32, v: u32, ) -> Weight {
+        Weight::from_parts(48_000_000, 4_800)
+            .saturating_add(Weight::from_parts(2_000, 0).saturating_mul(k.into()))
+            .saturating_add(Weight::from_parts(65_000, 0).saturating_mul(v.into()))
+            .saturating_add(Weight::from_parts(0, 32).saturating_mul(v.into()))
+            .saturating_add(RocksDbWeight::get().reads(4_u64))
+            .saturating_add(RocksDbWeight::get().writes(4_u64))
+    }
+    fn update_quantum_key(k: u32, ) -> Weight {
+        Weight::from_parts(18_000_000, 3_700)
+            .saturating_add(Weight::from_parts(1_500, 0).saturating_mul(k.into()))
+            .saturating_add(RocksDbWeight::get().reads(1_u64))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+    }
+    fn go_offline(v: u32, ) -> Weight {
+        Weight::from_parts(22_000_000, 3_700)
+            .saturating_add(Weight::from_parts(55_000, 0).saturating_mul(v.into()))
+            .saturating_add(Weight::from_parts(0, 32).saturating_mul(v.into()))
+            .saturating_add(RocksDbWeight::get().reads(2_u64))
+            .saturating_add(RocksDbWeight::get().writes(2_u64))
+    }
+    fn go_online(v: u32, ) -> Weight {
+        Weight::from_parts(22_000_000, 3_700)
+            .saturating_add(Weight::from_parts(50_000, 0).saturating_mul(v.into()))
+            .saturating_add(Weight::from_parts(0, 32).saturating_mul(v.into()))
+            .saturating_add(RocksDbWeight::get().reads(2_u64))
+            .saturating_add(RocksDbWeight::get().writes(2_u64))
+    }
+    fn leave(v: u32, ) -> Weight {
+        Weight::from_parts(24_000_000, 3_700)
+            .saturating_add(Weight::from_parts(55_000, 0).saturating_mul(v.into()))
+            .saturating_add(Weight::from_parts(0, 32).saturating_mul(v.into()))
+            .saturating_add(RocksDbWeight::get().reads(2_u64))
+            .saturating_add(RocksDbWeight::get().writes(3_u64))
+    }
+    fn submit_operation(d: u32, v: u32, ) -> Weight {
+        Weight::from_parts(62_000_000, 5_200)
+            .saturating_add(Weight::from_parts(2_000, 0).saturating_mul(d.into()))
+            .saturating_add(Weight::from_parts(9_500_000, 0).saturating_mul(v.into()))
+            .saturating_add(Weight::from_parts(0, 2_700).saturating_mul(v.into()))
+            .saturating_add(RocksDbWeight::get().reads(4_u64))
+            .saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(v.into())))
+            .saturating_add(RocksDbWeight::get().writes(13_u64))
+    }
+    fn vote_validation() -> Weight {
+        Weight::from_parts(185_000_000, 27_000)
+            .saturating_add(RocksDbWeight::get().reads(25_u64))
+            .saturating_add(RocksDbWeight::get().writes(43_u64))
+    }
+    fn withdraw_stake() -> Weight {
+        Weight::from_parts(46_000_000, 4_800)
+            .saturating_add(RocksDbWeight::get().reads(4_u64))
+            .saturating_add(RocksDbWeight::get().writes(4_u64))
+    }
+    fn cancel_slash() -> Weight {
+        Weight::from_parts(16_000_000, 3_600)
+            .saturating_add(RocksDbWeight::get().reads(1_u64))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+    }
+    fn apply_slashes(s: u32, ) -> Weight {
+        Weight::from_parts(9_000_000, 3_500)
+            .saturating_add(Weight::from_parts(72_000_000, 0).saturating_mul(s.into()))
+            .saturating_add(Weight::from_parts(0, 7_400).saturating_mul(s.into()))
+            .saturating_add(RocksDbWeight::get().reads(3_u64))
+            .saturating_add(RocksDbWeight::get().reads((6_u64).saturating_mul(s.into())))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+            .saturating_add(RocksDbWeight::get().writes((6_u64).saturating_mul(s.into())))
+    }
+    fn expire_operation() -> Weight {
+        Weight::from_parts(128_000_000, 9_400)
+            .saturating_add(RocksDbWeight::get().reads(8_u64))
+            .saturating_add(RocksDbWeight::get().writes(37_u64))
+    }
+    fn prune_expired(p: u32, ) -> Weight {
+        Weight::from_parts(8_000_000, 3_500)
+            .saturating_add(Weight::from_parts(6_200_000, 0).saturating_mul(p.into()))
+            .saturating_add(Weight::from_parts(0, 2_500).saturating_mul(p.into()))
+            .saturating_add(RocksDbWeight::get().reads(3_u64))
+            .saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(p.into())))
+            .saturating_add(RocksDbWeight::get().writes(1_u64))
+            .saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(p.into())))
+    }
+}
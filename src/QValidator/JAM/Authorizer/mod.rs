@@ -2,61 +2,332 @@
 // Core authorization component for IMRT
 
 use super::super::lib::*;
-use sp_std::prelude::*;
+use sp_std::{prelude::*, vec};
+use sp_core::H256;
+use sp_runtime::{
+    helpers_128bit::multiply_by_rational_with_rounding,
+    traits::{BlakeTwo256, Hash, UniqueSaturatedInto},
+    Rounding,
+};
 use permaweb_lib::profile::{Profile, Zone, Wallet};
 
+pub mod signature;
+
+// Validator public key, the signature scheme it belongs to and its
+// weight under a weighted threshold
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode)]
+pub struct ValidatorKey {
+    pub key_type: QuantumKeyType,
+    pub public_key: Vec<u8>,
+    pub weight: u128,
+}
+
+// Condition for a set of signers to authorize a transaction
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Encode, Decode)]
+pub enum Threshold {
+    // At least this many distinct signers
+    Signers(u32),
+    // Signers holding at least `numerator / denominator` of the total
+    // weight, and more than none of it
+    Weight { numerator: u32, denominator: u32 },
+}
+
+// Signature by the validator at `validator_index` in the key set
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode)]
+pub struct SignerSignature {
+    pub validator_index: u32,
+    pub signature: Vec<u8>,
+}
+
+// Successful authorization
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Authorization {
+    // Indices of the validators whose signatures verified, ascending
+    pub signers: Vec<u32>,
+    // Total weight of the signers
+    pub signed_weight: u128,
+}
+
+// Reasons a transaction is not authorized
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AuthorizationError {
+    // No validator key at this index
+    UnknownValidator(u32),
+    // The validator signed more than once
+    DuplicateSigner(u32),
+    // The validator's signature does not verify
+    InvalidSignature(u32),
+    // Fewer distinct signers than the threshold
+    ThresholdNotMet { signed: u32, threshold: u32 },
+    // Signers hold less than the required weight
+    WeightNotMet { signed_weight: u128, required_weight: u128 },
+    // No key set is active at this epoch
+    UnknownEpoch(u64),
+}
+
+// Validator keys and threshold in force from `epoch` until the next
+// key set
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode)]
+pub struct KeySet {
+    pub epoch: u64,
+    pub validator_keys: Vec<ValidatorKey>,
+    pub threshold: Threshold,
+}
+
+impl KeySet {
+    // Weight of all validator keys
+    pub fn total_weight(&self) -> u128 {
+        self.validator_keys.iter().fold(0, |total, key| total.saturating_add(key.weight))
+    }
+
+    // Commitment to the epoch, keys and threshold of the set
+    pub fn hash(&self) -> H256 {
+        BlakeTwo256::hash_of(self)
+    }
+
+    // Authorize a message whose distinct signers meet the threshold
+    //
+    // Each signature names its signer by index into the validator key set.
+    // Unknown and repeated signers and invalid signatures reject the whole
+    // set, so the returned signers are exactly the validators whose
+    // signatures were checked.
+    pub fn authorize(
+        &self,
+        message: &[u8],
+        signatures: &[SignerSignature],
+    ) -> Result<Authorization, AuthorizationError> {
+        let mut signed = vec![false; self.validator_keys.len()];
+        for SignerSignature { validator_index, signature } in signatures {
+            let index = *validator_index as usize;
+            let key = self
+                .validator_keys
+                .get(index)
+                .ok_or(AuthorizationError::UnknownValidator(*validator_index))?;
+            if signed[index] {
+                return Err(AuthorizationError::DuplicateSigner(*validator_index));
+            }
+            if !signature::verify(key.key_type, message, signature, &key.public_key) {
+                return Err(AuthorizationError::InvalidSignature(*validator_index));
+            }
+            signed[index] = true;
+        }
+
+        let signers: Vec<u32> = signed
+            .iter()
+            .enumerate()
+            .filter(|(_, signed)| **signed)
+            .map(|(index, _)| index as u32)
+            .collect();
+        let signed_weight = signers
+            .iter()
+            .fold(0u128, |total, index| total.saturating_add(self.validator_keys[*index as usize].weight));
+
+        match self.threshold {
+            Threshold::Signers(threshold) => {
+                let count = signers.len() as u32;
+                if count < threshold {
+                    return Err(AuthorizationError::ThresholdNotMet { signed: count, threshold });
+                }
+            }
+            Threshold::Weight { numerator, denominator } => {
+                // A zero denominator requires more weight than can exist
+                let required_weight = multiply_by_rational_with_rounding(
+                    self.total_weight(),
+                    numerator.into(),
+                    denominator.into(),
+                    Rounding::Up,
+                )
+                .unwrap_or(u128::MAX)
+                .max(1);
+                if signed_weight < required_weight {
+                    return Err(AuthorizationError::WeightNotMet { signed_weight, required_weight });
+                }
+            }
+        }
+
+        Ok(Authorization { signers, signed_weight })
+    }
+}
+
+// Handoff to `next`, signed by the outgoing key set
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode)]
+pub struct KeySetTransition {
+    pub next: KeySet,
+    pub signatures: Vec<SignerSignature>,
+}
+
+impl KeySetTransition {
+    // Message the outgoing set signs to hand off to `next`
+    //
+    // Binding the outgoing set's commitment keeps a handoff from being
+    // replayed on top of any other set.
+    pub fn message(previous: &KeySet, next: &KeySet) -> Vec<u8> {
+        (TRANSITION_CONTEXT, previous.hash(), next.epoch, next.hash()).encode()
+    }
+}
+
+// Chain of handoffs from a trusted key set to a later one, for light
+// clients that follow the key sets without the transactions between them
+pub type TransitionProof = Vec<KeySetTransition>;
+
+// Domain separator of handoff messages, so no transaction signature
+// doubles as a handoff
+const TRANSITION_CONTEXT: &[u8] = b"JAM key set transition";
+
+// Reasons a key set transition is rejected
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TransitionError {
+    // The next set does not start after the current one
+    StaleEpoch { current: u64, next: u64 },
+    // The outgoing set did not authorize the handoff
+    Unauthorized(AuthorizationError),
+}
+
+// Check that `previous` handed off to `transition.next`
+fn check_transition(previous: &KeySet, transition: &KeySetTransition) -> Result<Authorization, TransitionError> {
+    if transition.next.epoch <= previous.epoch {
+        return Err(TransitionError::StaleEpoch { current: previous.epoch, next: transition.next.epoch });
+    }
+    previous
+        .authorize(&KeySetTransition::message(previous, &transition.next), &transition.signatures)
+        .map_err(TransitionError::Unauthorized)
+}
+
+// Follow `proof` from the `trusted` key set, returning the last set it
+// hands off to
+//
+// An empty proof returns the trusted set itself.
+pub fn verify_transition_proof(trusted: &KeySet, proof: &[KeySetTransition]) -> Result<KeySet, TransitionError> {
+    let mut current = trusted;
+    for transition in proof {
+        check_transition(current, transition)?;
+        current = &transition.next;
+    }
+    Ok(current.clone())
+}
+
 // JAM Authorizer
+//
+// Starts from a genesis key set and keeps every handoff since, so
+// signatures can be checked against the set of any epoch.
 pub struct JAMAuthorizer {
-    validator_keys: Vec<Vec<u8>>,
-    threshold: u32,
+    genesis: KeySet,
+    transitions: Vec<KeySetTransition>,
     actor: ActorX,
 }
 
 impl JAMAuthorizer {
-    pub fn new(validator_keys: Vec<Vec<u8>>, threshold: u32) -> Self {
+    // Authorizer requiring `threshold` distinct signers
+    pub fn new(validator_keys: Vec<ValidatorKey>, threshold: u32) -> Self {
+        Self::with_threshold(validator_keys, Threshold::Signers(threshold))
+    }
+
+    pub fn with_threshold(validator_keys: Vec<ValidatorKey>, threshold: Threshold) -> Self {
+        Self::from_genesis(KeySet { epoch: 0, validator_keys, threshold })
+    }
+
+    // Authorizer whose first key set is `genesis`
+    pub fn from_genesis(genesis: KeySet) -> Self {
         let actor = ActorX::new("IMRT-JAM-Authorizer");
         
         Self {
-            validator_keys,
-            threshold,
+            genesis,
+            transitions: Vec::new(),
             actor,
         }
     }
-    
-    pub fn authorize_transaction(&self, tx_data: &[u8], signatures: Vec<Vec<u8>>) -> Result<bool, &'static str> {
-        // Verify threshold signature count
-        if signatures.len() < self.threshold as usize {
-            return Err("Insufficient signatures for threshold");
-        }
-        
-        // Verify each signature
-        let mut valid_count = 0;
-        for (i, sig) in signatures.iter().enumerate() {
-            if i >= self.validator_keys.len() {
-                break;
-            }
-            
-            if self.verify_signature(tx_data, sig, &self.validator_keys[i]) {
-                valid_count += 1;
-            }
-        }
-        
-        // Check if threshold is met
-        if valid_count >= self.threshold {
-            Ok(true)
-        } else {
-            Err("Failed to meet signature threshold")
+
+    // Authorizer over the active QValidator set, as returned by the
+    // `staked_keys` runtime API, weighting each key by the validator's
+    // stake
+    //
+    // Each validator signs with the key type it registered its key under,
+    // so the set can move to hybrid keys one validator at a time. Stakes
+    // beyond `u128` saturate.
+    pub fn from_stake<AccountId, Balance: UniqueSaturatedInto<u128>>(
+        staked_keys: Vec<(AccountId, QuantumKeyType, Vec<u8>, Balance)>,
+        numerator: u32,
+        denominator: u32,
+    ) -> Self {
+        let validator_keys = staked_keys
+            .into_iter()
+            .map(|(_, key_type, public_key, stake)| ValidatorKey {
+                key_type,
+                public_key,
+                weight: stake.unique_saturated_into(),
+            })
+            .collect();
+        Self::with_threshold(validator_keys, Threshold::Weight { numerator, denominator })
+    }
+
+    // Latest key set
+    pub fn current_key_set(&self) -> &KeySet {
+        self.transitions.last().map_or(&self.genesis, |transition| &transition.next)
+    }
+
+    // Key set active at `epoch`, if it is not before genesis
+    pub fn key_set_at(&self, epoch: u64) -> Option<&KeySet> {
+        match self.key_set_index(epoch)? {
+            0 => Some(&self.genesis),
+            index => Some(&self.transitions[index - 1].next),
         }
     }
-    
-    fn verify_signature(&self, data: &[u8], signature: &[u8], public_key: &[u8]) -> bool {
-        // Signature verification logic
-        // For different quantum key types
-        true
+
+    // Weight of all keys in the current set
+    pub fn total_weight(&self) -> u128 {
+        self.current_key_set().total_weight()
     }
-    
-    // Secure key management implementation
-    #![cfg_attr(not(feature = "std"), no_std)]
+
+    // Hand off to `transition.next`, which the current set must authorize
+    pub fn rotate(&mut self, transition: KeySetTransition) -> Result<Authorization, TransitionError> {
+        let authorization = check_transition(self.current_key_set(), &transition)?;
+        self.transitions.push(transition);
+        Ok(authorization)
+    }
+
+    // Handoffs from the set active at `from` to the set active at `to`
+    //
+    // `None` if either epoch is before genesis or `to` is before `from`.
+    pub fn transition_proof(&self, from: u64, to: u64) -> Option<TransitionProof> {
+        let start = self.key_set_index(from)?;
+        let end = self.key_set_index(to)?;
+        self.transitions.get(start..end).map(<[_]>::to_vec)
+    }
+
+    // Authorize a transaction under the current key set
+    pub fn authorize_transaction(
+        &self,
+        tx_data: &[u8],
+        signatures: Vec<SignerSignature>,
+    ) -> Result<Authorization, AuthorizationError> {
+        self.current_key_set().authorize(tx_data, &signatures)
+    }
+
+    // Authorize a transaction under the key set active at `epoch`
+    pub fn authorize_transaction_at(
+        &self,
+        epoch: u64,
+        tx_data: &[u8],
+        signatures: Vec<SignerSignature>,
+    ) -> Result<Authorization, AuthorizationError> {
+        self.key_set_at(epoch)
+            .ok_or(AuthorizationError::UnknownEpoch(epoch))?
+            .authorize(tx_data, &signatures)
+    }
+
+    // Position of the key set active at `epoch`, counting genesis as 0
+    //
+    // Epochs increase along the handoffs, so the last set starting no
+    // later than `epoch` is the active one.
+    fn key_set_index(&self, epoch: u64) -> Option<usize> {
+        match self.transitions.iter().rposition(|transition| transition.next.epoch <= epoch) {
+            Some(index) => Some(index + 1),
+            None => (self.genesis.epoch <= epoch).then_some(0),
+        }
+    }
+}
+
+// Secure key management implementation
 use ink_lang as ink;
 use ink_storage::{
     traits::SpreadAllocate,
@@ -432,7 +703,6 @@ mod secure_key_management {
         QuantumProofInvalid,
     }
 }
-}
 
 // Error correction integrations
 mod error_correction {
@@ -460,3 +730,339 @@ mod error_correction {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_core::{ed25519, Pair};
+    use sp_runtime::AccountId32;
+
+    const TX: &[u8] = b"JAM transaction";
+
+    fn pair(index: u32) -> ed25519::Pair {
+        ed25519::Pair::from_seed(&[index as u8; 32])
+    }
+
+    fn authorizer(threshold: u32) -> JAMAuthorizer {
+        let keys = (0..4)
+            .map(|index| ValidatorKey {
+                key_type: QuantumKeyType::ECDSA,
+                public_key: pair(index).public().0.to_vec(),
+                weight: 1,
+            })
+            .collect();
+        JAMAuthorizer::new(keys, threshold)
+    }
+
+    // Stakes 10, 20, 30 and 40, authorized by two thirds of 100
+    fn weighted() -> JAMAuthorizer {
+        JAMAuthorizer::from_stake(staked_keys(&[10, 20, 30, 40]), 2, 3)
+    }
+
+    // Runtime API `staked_keys` of validators `0..` with the given stakes
+    fn staked_keys(stakes: &[u128]) -> Vec<(AccountId32, QuantumKeyType, Vec<u8>, u128)> {
+        stakes
+            .iter()
+            .enumerate()
+            .map(|(index, stake)| {
+                let public = pair(index as u32).public();
+                (AccountId32::from(public.0), QuantumKeyType::ECDSA, public.0.to_vec(), *stake)
+            })
+            .collect()
+    }
+
+    fn signed_by(index: u32) -> SignerSignature {
+        signed_message(index, index, TX)
+    }
+
+    // Signature by key `key` at position `validator_index` in its set
+    fn signed_message(validator_index: u32, key: u32, message: &[u8]) -> SignerSignature {
+        SignerSignature { validator_index, signature: pair(key).sign(message).0.to_vec() }
+    }
+
+    // Set of `keys` from `epoch`, requiring two signers
+    fn key_set(epoch: u64, keys: &[u32]) -> KeySet {
+        let validator_keys = keys
+            .iter()
+            .map(|index| ValidatorKey {
+                key_type: QuantumKeyType::ECDSA,
+                public_key: pair(*index).public().0.to_vec(),
+                weight: 1,
+            })
+            .collect();
+        KeySet { epoch, validator_keys, threshold: Threshold::Signers(2) }
+    }
+
+    // Handoff from `previous` to `next`, signed by the first two keys
+    // of `previous`
+    fn handoff(previous: &KeySet, previous_keys: &[u32], next: KeySet) -> KeySetTransition {
+        let message = KeySetTransition::message(previous, &next);
+        let signatures = vec![
+            signed_message(0, previous_keys[0], &message),
+            signed_message(1, previous_keys[1], &message),
+        ];
+        KeySetTransition { next, signatures }
+    }
+
+    // Genesis keys 0 to 3, then keys 4 to 7 from epoch 5 and keys 8 to 11
+    // from epoch 9
+    fn rotated() -> JAMAuthorizer {
+        let genesis = key_set(0, &[0, 1, 2, 3]);
+        let second = key_set(5, &[4, 5, 6, 7]);
+        let mut authorizer = JAMAuthorizer::from_genesis(genesis.clone());
+        assert!(authorizer.rotate(handoff(&genesis, &[0, 1, 2, 3], second.clone())).is_ok());
+        assert!(authorizer.rotate(handoff(&second, &[4, 5, 6, 7], key_set(9, &[8, 9, 10, 11]))).is_ok());
+        authorizer
+    }
+
+    #[test]
+    fn reports_exactly_who_signed() {
+        let authorization = authorizer(2).authorize_transaction(TX, vec![signed_by(3), signed_by(1)]);
+        assert_eq!(authorization, Ok(Authorization { signers: vec![1, 3], signed_weight: 2 }));
+    }
+
+    #[test]
+    fn missing_signers_do_not_shift_later_signatures() {
+        let authorization = authorizer(3).authorize_transaction(TX, vec![signed_by(0), signed_by(2), signed_by(3)]);
+        assert_eq!(authorization, Ok(Authorization { signers: vec![0, 2, 3], signed_weight: 3 }));
+    }
+
+    #[test]
+    fn rejects_duplicate_signers() {
+        let authorization = authorizer(2).authorize_transaction(TX, vec![signed_by(0), signed_by(0)]);
+        assert_eq!(authorization, Err(AuthorizationError::DuplicateSigner(0)));
+    }
+
+    #[test]
+    fn rejects_unknown_and_invalid_signers() {
+        let authorizer = authorizer(1);
+        let unknown = SignerSignature { validator_index: 4, ..signed_by(0) };
+        assert_eq!(
+            authorizer.authorize_transaction(TX, vec![signed_by(0), unknown]),
+            Err(AuthorizationError::UnknownValidator(4))
+        );
+
+        let misattributed = SignerSignature { validator_index: 2, ..signed_by(1) };
+        assert_eq!(
+            authorizer.authorize_transaction(TX, vec![misattributed]),
+            Err(AuthorizationError::InvalidSignature(2))
+        );
+        assert_eq!(
+            authorizer.authorize_transaction(b"other", vec![signed_by(0)]),
+            Err(AuthorizationError::InvalidSignature(0))
+        );
+    }
+
+    #[test]
+    fn enforces_threshold() {
+        let authorizer = authorizer(3);
+        assert_eq!(
+            authorizer.authorize_transaction(TX, vec![signed_by(0), signed_by(1)]),
+            Err(AuthorizationError::ThresholdNotMet { signed: 2, threshold: 3 })
+        );
+        assert_eq!(
+            authorizer.authorize_transaction(TX, vec![]),
+            Err(AuthorizationError::ThresholdNotMet { signed: 0, threshold: 3 })
+        );
+    }
+
+    #[test]
+    fn weighted_threshold_follows_stake() {
+        let authorizer = weighted();
+        assert_eq!(authorizer.total_weight(), 100);
+
+        assert_eq!(
+            authorizer.authorize_transaction(TX, vec![signed_by(3), signed_by(2)]),
+            Ok(Authorization { signers: vec![2, 3], signed_weight: 70 })
+        );
+        // Three of four signers, but only 60 of 100
+        assert_eq!(
+            authorizer.authorize_transaction(TX, vec![signed_by(0), signed_by(1), signed_by(2)]),
+            Err(AuthorizationError::WeightNotMet { signed_weight: 60, required_weight: 67 })
+        );
+        assert_eq!(
+            authorizer.authorize_transaction(TX, vec![signed_by(0), signed_by(0)]),
+            Err(AuthorizationError::DuplicateSigner(0))
+        );
+    }
+
+    #[test]
+    fn stake_sets_decode_from_the_runtime_api() {
+        // As returned by `QValidatorApi::staked_keys` for a `u64` balance
+        let encoded = staked_keys(&[10, 20, 30, 40])
+            .into_iter()
+            .map(|(who, key_type, public_key, stake)| (who, key_type, public_key, stake as u64))
+            .collect::<Vec<_>>()
+            .encode();
+        let staked_keys = Vec::<(AccountId32, QuantumKeyType, Vec<u8>, u64)>::decode(&mut &encoded[..]).unwrap();
+
+        let authorizer = JAMAuthorizer::from_stake(staked_keys.clone(), 2, 3);
+        assert_eq!(authorizer.total_weight(), 100);
+        let keys = &authorizer.current_key_set().validator_keys;
+        for ((_, key_type, public_key, stake), key) in staked_keys.into_iter().zip(keys) {
+            assert_eq!(key.key_type, key_type);
+            assert_eq!(key.public_key, public_key);
+            assert_eq!(key.weight, stake as u128);
+        }
+        assert_eq!(authorizer.current_key_set(), weighted().current_key_set());
+    }
+
+    #[test]
+    fn weighted_threshold_needs_some_weight() {
+        let authorizer = JAMAuthorizer::from_stake(staked_keys(&[0]), 2, 3);
+        assert_eq!(
+            authorizer.authorize_transaction(TX, vec![signed_by(0)]),
+            Err(AuthorizationError::WeightNotMet { signed_weight: 0, required_weight: 1 })
+        );
+
+        let unreachable = JAMAuthorizer::from_stake(staked_keys(&[10]), 1, 0);
+        assert_eq!(
+            unreachable.authorize_transaction(TX, vec![signed_by(0)]),
+            Err(AuthorizationError::WeightNotMet { signed_weight: 10, required_weight: u128::MAX })
+        );
+    }
+
+    #[test]
+    fn rotation_needs_the_outgoing_threshold() {
+        let genesis = key_set(0, &[0, 1, 2, 3]);
+        let next = key_set(1, &[4, 5, 6, 7]);
+        let mut authorizer = JAMAuthorizer::from_genesis(genesis.clone());
+
+        let mut transition = handoff(&genesis, &[0, 1, 2, 3], next.clone());
+        transition.signatures.pop();
+        assert_eq!(
+            authorizer.rotate(transition),
+            Err(TransitionError::Unauthorized(AuthorizationError::ThresholdNotMet { signed: 1, threshold: 2 }))
+        );
+
+        // Signed by the incoming keys instead of the outgoing ones
+        assert_eq!(
+            authorizer.rotate(handoff(&genesis, &[4, 5, 6, 7], next.clone())),
+            Err(TransitionError::Unauthorized(AuthorizationError::InvalidSignature(0)))
+        );
+        assert_eq!(authorizer.current_key_set(), &genesis);
+
+        assert_eq!(
+            authorizer.rotate(handoff(&genesis, &[0, 1, 2, 3], next.clone())),
+            Ok(Authorization { signers: vec![0, 1], signed_weight: 2 })
+        );
+        assert_eq!(authorizer.current_key_set(), &next);
+    }
+
+    #[test]
+    fn rotation_moves_forward_and_binds_the_outgoing_set() {
+        let genesis = key_set(3, &[0, 1, 2, 3]);
+        let mut authorizer = JAMAuthorizer::from_genesis(genesis.clone());
+        assert_eq!(
+            authorizer.rotate(handoff(&genesis, &[0, 1, 2, 3], key_set(3, &[4, 5, 6, 7]))),
+            Err(TransitionError::StaleEpoch { current: 3, next: 3 })
+        );
+
+        // A handoff signed for another outgoing set does not carry over
+        let other = key_set(3, &[0, 1, 2]);
+        assert_eq!(
+            authorizer.rotate(handoff(&other, &[0, 1, 2], key_set(4, &[4, 5, 6, 7]))),
+            Err(TransitionError::Unauthorized(AuthorizationError::InvalidSignature(0)))
+        );
+    }
+
+    #[test]
+    fn authorizes_against_the_set_of_an_epoch() {
+        let authorizer = rotated();
+        assert_eq!(authorizer.key_set_at(4).map(|key_set| key_set.epoch), Some(0));
+        assert_eq!(authorizer.key_set_at(5).map(|key_set| key_set.epoch), Some(5));
+        assert_eq!(authorizer.key_set_at(100).map(|key_set| key_set.epoch), Some(9));
+
+        let old_signatures = vec![signed_by(0), signed_by(1)];
+        assert_eq!(
+            authorizer.authorize_transaction_at(4, TX, old_signatures.clone()),
+            Ok(Authorization { signers: vec![0, 1], signed_weight: 2 })
+        );
+        assert_eq!(
+            authorizer.authorize_transaction_at(5, TX, old_signatures.clone()),
+            Err(AuthorizationError::InvalidSignature(0))
+        );
+        assert_eq!(
+            authorizer.authorize_transaction(TX, vec![signed_message(0, 8, TX), signed_message(3, 11, TX)]),
+            Ok(Authorization { signers: vec![0, 3], signed_weight: 2 })
+        );
+
+        let late = JAMAuthorizer::from_genesis(key_set(2, &[0, 1, 2, 3]));
+        assert_eq!(late.key_set_at(1), None);
+        assert_eq!(late.authorize_transaction_at(1, TX, old_signatures), Err(AuthorizationError::UnknownEpoch(1)));
+    }
+
+    #[test]
+    fn light_clients_follow_transition_proofs() {
+        let authorizer = rotated();
+        let genesis = authorizer.key_set_at(0).unwrap().clone();
+
+        let proof = authorizer.transition_proof(0, 9).unwrap();
+        assert_eq!(proof.len(), 2);
+        assert_eq!(verify_transition_proof(&genesis, &proof).as_ref(), Ok(authorizer.current_key_set()));
+
+        // From inside an epoch to inside a later one
+        let proof = authorizer.transition_proof(6, 20).unwrap();
+        assert_eq!(proof.len(), 1);
+        let second = authorizer.key_set_at(6).unwrap();
+        assert_eq!(verify_transition_proof(second, &proof).as_ref(), Ok(authorizer.current_key_set()));
+        assert_eq!(authorizer.transition_proof(6, 8), Some(vec![]));
+        assert_eq!(authorizer.transition_proof(9, 0), None);
+
+        // Proofs survive encoding and reject a swapped-in key set
+        let proof = TransitionProof::decode(&mut &authorizer.transition_proof(0, 9).unwrap().encode()[..]).unwrap();
+        assert!(verify_transition_proof(&genesis, &proof).is_ok());
+        let mut forged = proof.clone();
+        forged[1].next = key_set(9, &[0, 1, 2, 3]);
+        assert_eq!(
+            verify_transition_proof(&genesis, &forged),
+            Err(TransitionError::Unauthorized(AuthorizationError::InvalidSignature(0)))
+        );
+        // Skipping a handoff breaks the chain
+        assert_eq!(
+            verify_transition_proof(&genesis, &proof[1..]),
+            Err(TransitionError::Unauthorized(AuthorizationError::InvalidSignature(0)))
+        );
+    }
+
+    #[test]
+    fn stake_sets_mix_classical_and_hybrid_keys() {
+        let hybrid = signature::HybridPair::new(pair(3), pqc_dilithium::Keypair::generate());
+        let mut staked_keys = staked_keys(&[10, 20, 30]);
+        staked_keys.push((AccountId32::from(pair(3).public().0), QuantumKeyType::Hybrid, hybrid.public().encode(), 40));
+        let authorizer = JAMAuthorizer::from_stake(staked_keys, 2, 3);
+        let key_types: Vec<_> =
+            authorizer.current_key_set().validator_keys.iter().map(|key| key.key_type).collect();
+        assert_eq!(
+            key_types,
+            vec![QuantumKeyType::ECDSA, QuantumKeyType::ECDSA, QuantumKeyType::ECDSA, QuantumKeyType::Hybrid]
+        );
+
+        let hybrid_signature = SignerSignature { validator_index: 3, signature: hybrid.sign(TX).encode() };
+        assert_eq!(
+            authorizer.authorize_transaction(TX, vec![signed_by(2), hybrid_signature]),
+            Ok(Authorization { signers: vec![2, 3], signed_weight: 70 })
+        );
+        // The Ed25519 half of a hybrid key does not sign for it alone
+        assert_eq!(
+            authorizer.authorize_transaction(TX, vec![signed_by(2), signed_by(3)]),
+            Err(AuthorizationError::InvalidSignature(3))
+        );
+    }
+
+    #[test]
+    fn stake_sets_keep_registered_key_types() {
+        // A key registered as classical that happens to decode as a hybrid
+        // key is not verified as one
+        let hybrid = signature::HybridPair::new(pair(0), pqc_dilithium::Keypair::generate());
+        let staked_key = (AccountId32::from(pair(0).public().0), QuantumKeyType::ECDSA, hybrid.public().encode(), 10);
+        let authorizer = JAMAuthorizer::from_stake(vec![staked_key], 1, 1);
+        assert_eq!(authorizer.current_key_set().validator_keys[0].key_type, QuantumKeyType::ECDSA);
+
+        let hybrid_signature = SignerSignature { validator_index: 0, signature: hybrid.sign(TX).encode() };
+        assert_eq!(
+            authorizer.authorize_transaction(TX, vec![hybrid_signature]),
+            Err(AuthorizationError::InvalidSignature(0))
+        );
+    }
+}
@@ -0,0 +1,366 @@
+// Signature verification for validator keys
+//
+// Each `QuantumKeyType` maps to one signature scheme:
+// - `ECDSA`: classical keys, Ed25519 for 32-byte keys and ECDSA over
+//   secp256k1 for 33-byte compressed keys. ECDSA signatures are 65-byte
+//   recoverable signatures over the BLAKE2b-256 hash of the message, as
+//   produced by `sp_core::ecdsa`.
+// - `LatticeBasedKEM`: Dilithium, with the parameter set selected by the
+//   `pqc_dilithium` mode feature.
+// - `HashBased`: SPHINCS+-SHAKE-128f-simple.
+// - `Hybrid`: Ed25519 and Dilithium together. Keys are SCALE-encoded
+//   `HybridPublicKey`s, as defined by the QValidator pallet, and
+//   signatures SCALE-encoded `HybridSignature`s:
+//   the 32-byte Ed25519 key or 64-byte signature, then the
+//   length-prefixed Dilithium key or signature. Both halves sign the
+//   message under `HYBRID_CONTEXT`, and both must verify.
+//
+// `Multivariate` keys have no scheme yet and never verify.
+
+use super::super::super::lib::QuantumKeyType;
+use pqcrypto_sphincsplus::sphincsshake128fsimple as sphincs;
+use pqcrypto_traits::sign::{DetachedSignature as _, PublicKey as _};
+pub use quantum_validator::HybridPublicKey;
+use scale::{Decode, DecodeAll, Encode};
+use sp_core::{ed25519, hashing::blake2_256, Pair as _};
+use sp_runtime::traits::Verify;
+use sp_std::prelude::*;
+
+// Domain separator of messages signed by hybrid keys, so neither half of
+// a hybrid signature verifies as a plain Ed25519 or Dilithium signature
+// of the message
+const HYBRID_CONTEXT: &[u8] = b"JAM hybrid signature";
+
+// Hybrid keys registered with the pallet must verify here
+const _: () = assert!(pqc_dilithium::PUBLICKEYBYTES == HybridPublicKey::DILITHIUM_KEY_LENGTH);
+
+// Composite Ed25519 and Dilithium signature
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode)]
+pub struct HybridSignature {
+    pub ed25519: [u8; 64],
+    pub dilithium: Vec<u8>,
+}
+
+// Signing keys of a hybrid public key
+pub struct HybridPair {
+    ed25519: ed25519::Pair,
+    dilithium: pqc_dilithium::Keypair,
+}
+
+impl HybridPair {
+    pub fn new(ed25519: ed25519::Pair, dilithium: pqc_dilithium::Keypair) -> Self {
+        Self { ed25519, dilithium }
+    }
+
+    pub fn public(&self) -> HybridPublicKey {
+        HybridPublicKey { ed25519: self.ed25519.public().0, dilithium: self.dilithium.public.to_vec() }
+    }
+
+    pub fn sign(&self, message: &[u8]) -> HybridSignature {
+        let message = hybrid_message(message);
+        HybridSignature {
+            ed25519: self.ed25519.sign(&message).0,
+            dilithium: self.dilithium.sign(&message).to_vec(),
+        }
+    }
+}
+
+// Verify `signature` over `message` with a public key of the given type
+//
+// Malformed keys and signatures never verify.
+pub fn verify(key_type: QuantumKeyType, message: &[u8], signature: &[u8], public_key: &[u8]) -> bool {
+    match key_type {
+        QuantumKeyType::ECDSA => verify_classical(message, signature, public_key),
+        QuantumKeyType::LatticeBasedKEM => verify_dilithium(message, signature, public_key),
+        QuantumKeyType::HashBased => verify_sphincs(message, signature, public_key),
+        QuantumKeyType::Hybrid => verify_hybrid(message, signature, public_key),
+        QuantumKeyType::Multivariate => false,
+    }
+}
+
+// Verify with Ed25519 or ECDSA, depending on the public key length
+fn verify_classical(message: &[u8], signature: &[u8], public_key: &[u8]) -> bool {
+    match public_key.len() {
+        32 => verify_ed25519(message, signature, public_key),
+        33 => verify_ecdsa(message, signature, public_key),
+        _ => false,
+    }
+}
+
+fn verify_ed25519(message: &[u8], signature: &[u8], public_key: &[u8]) -> bool {
+    let (Ok(signature), Ok(public_key)) =
+        (ed25519::Signature::try_from(signature), ed25519::Public::try_from(public_key))
+    else {
+        return false;
+    };
+    signature.verify(message, &public_key)
+}
+
+fn verify_ecdsa(message: &[u8], signature: &[u8], public_key: &[u8]) -> bool {
+    verify_ecdsa_prehashed(&blake2_256(message), signature, public_key)
+}
+
+// Verify a recoverable ECDSA signature over a 32-byte message hash by
+// recovering its compressed public key
+fn verify_ecdsa_prehashed(hash: &[u8; 32], signature: &[u8], public_key: &[u8]) -> bool {
+    let (Ok(signature), Ok(public_key)) = (<[u8; 65]>::try_from(signature), <[u8; 33]>::try_from(public_key)) else {
+        return false;
+    };
+    sp_io::crypto::secp256k1_ecdsa_recover_compressed(&signature, hash).map_or(false, |recovered| recovered == public_key)
+}
+
+fn verify_dilithium(message: &[u8], signature: &[u8], public_key: &[u8]) -> bool {
+    // `pqc_dilithium` indexes the key without checking its length
+    if public_key.len() != pqc_dilithium::PUBLICKEYBYTES || signature.len() != pqc_dilithium::SIGNBYTES {
+        return false;
+    }
+    pqc_dilithium::verify(signature, message, public_key).is_ok()
+}
+
+fn verify_sphincs(message: &[u8], signature: &[u8], public_key: &[u8]) -> bool {
+    let (Ok(signature), Ok(public_key)) =
+        (sphincs::DetachedSignature::from_bytes(signature), sphincs::PublicKey::from_bytes(public_key))
+    else {
+        return false;
+    };
+    sphincs::verify_detached_signature(&signature, message, &public_key).is_ok()
+}
+
+fn hybrid_message(message: &[u8]) -> Vec<u8> {
+    (HYBRID_CONTEXT, message).encode()
+}
+
+fn verify_hybrid(message: &[u8], signature: &[u8], public_key: &[u8]) -> bool {
+    let (Ok(signature), Some(public_key)) =
+        (HybridSignature::decode_all(&mut &signature[..]), HybridPublicKey::from_quantum_pubkey(public_key))
+    else {
+        return false;
+    };
+    let message = hybrid_message(message);
+    verify_ed25519(&message, &signature.ed25519, &public_key.ed25519)
+        && verify_dilithium(&message, &signature.dilithium, &public_key.dilithium)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    // RFC 8032, section 7.1, tests 1 and 2
+    const ED25519_VECTORS: [(&[u8], [u8; 32], [u8; 64]); 2] = [
+        (
+            b"",
+            hex!("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"),
+            hex!(
+                "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+            ),
+        ),
+        (
+            &[0x72],
+            hex!("3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c"),
+            hex!(
+                "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00"
+            ),
+        ),
+    ];
+
+    // `sp_core::ecdsa::Pair::from_seed(&[0x11; 32])` signing `ECDSA_MESSAGE`
+    // with RFC 6979 nonces
+    const ECDSA_MESSAGE: &[u8] = b"JAM authorization";
+    const ECDSA_PUBLIC: [u8; 33] = hex!("034f355bdcb7cc0af728ef3cceb9615d90684bb5b2ca5f859ab0f0b704075871aa");
+    const ECDSA_SIGNATURE: [u8; 65] = hex!(
+        "856d5470689683ee7c75997dfc5fbf055946017f959fcbd286fca1a2632b2a841ca133159d81f9c246cb52877d97f929b34455f7b125fff19b7a2581b2ccdcbe01"
+    );
+
+    // Project Wycheproof `ecdsa_secp256k1_sha256_test.json`, tcId 3: the
+    // DER signature as r || s followed by the recovery id that yields the
+    // test group's key, and the key compressed. The message is signed
+    // through its SHA-256 hash.
+    const WYCHEPROOF_MESSAGE: [u8; 6] = hex!("313233343030");
+    const WYCHEPROOF_PUBLIC: [u8; 33] = hex!("03b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f");
+    const WYCHEPROOF_SIGNATURE: [u8; 65] = hex!(
+        "813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc98323656ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba01"
+    );
+
+    // Entry 0 of the NIST KAT responses for round-3 Dilithium3 and
+    // SPHINCS+-SHAKE-128f-simple, generated with PQClean's `nistkat` and
+    // checked against the `nistkat-sha256` of its META.yml. The signatures
+    // are the leading signature bytes of the KAT's `sm`, with `msg` after.
+    const KAT_MESSAGE: [u8; 33] = hex!("d81c4d8d734fcbfbeade3d3f8a039faa2a2c9957e835ad55b22e75bf57bb556ac8");
+    const DILITHIUM_KAT_PUBLIC: &[u8] = include_bytes!("kat/dilithium3.pk");
+    const DILITHIUM_KAT_SIGNATURE: &[u8] = include_bytes!("kat/dilithium3.sig");
+    const SPHINCS_KAT_PUBLIC: &[u8] = include_bytes!("kat/sphincs-shake-128f-simple.pk");
+    const SPHINCS_KAT_SIGNATURE: &[u8] = include_bytes!("kat/sphincs-shake-128f-simple.sig");
+
+    fn flip_first(bytes: &[u8]) -> Vec<u8> {
+        let mut bytes = bytes.to_vec();
+        bytes[0] ^= 0x01;
+        bytes
+    }
+
+    fn flip_last(bytes: &[u8]) -> Vec<u8> {
+        let mut bytes = bytes.to_vec();
+        *bytes.last_mut().unwrap() ^= 0x01;
+        bytes
+    }
+
+    #[test]
+    fn ed25519_known_answers() {
+        for (message, public_key, signature) in ED25519_VECTORS {
+            assert!(verify(QuantumKeyType::ECDSA, message, &signature, &public_key));
+            assert!(!verify(QuantumKeyType::ECDSA, b"other", &signature, &public_key));
+            assert!(!verify(QuantumKeyType::ECDSA, message, &flip_last(&signature), &public_key));
+            assert!(!verify(QuantumKeyType::ECDSA, message, &signature[..63], &public_key));
+        }
+        let (message, public_key, _) = ED25519_VECTORS[0];
+        let (_, _, other_signature) = ED25519_VECTORS[1];
+        assert!(!verify(QuantumKeyType::ECDSA, message, &other_signature, &public_key));
+    }
+
+    #[test]
+    fn ecdsa_known_answer() {
+        assert!(verify(QuantumKeyType::ECDSA, ECDSA_MESSAGE, &ECDSA_SIGNATURE, &ECDSA_PUBLIC));
+        assert!(!verify(QuantumKeyType::ECDSA, b"other", &ECDSA_SIGNATURE, &ECDSA_PUBLIC));
+        assert!(!verify(QuantumKeyType::ECDSA, ECDSA_MESSAGE, &ECDSA_SIGNATURE[..64], &ECDSA_PUBLIC));
+
+        let mut signature = ECDSA_SIGNATURE;
+        signature[0] ^= 0x01;
+        assert!(!verify(QuantumKeyType::ECDSA, ECDSA_MESSAGE, &signature, &ECDSA_PUBLIC));
+    }
+
+    #[test]
+    fn secp256k1_known_answer() {
+        let hash = sp_core::hashing::sha2_256(&WYCHEPROOF_MESSAGE);
+        assert!(verify_ecdsa_prehashed(&hash, &WYCHEPROOF_SIGNATURE, &WYCHEPROOF_PUBLIC));
+        assert!(!verify_ecdsa_prehashed(&blake2_256(&WYCHEPROOF_MESSAGE), &WYCHEPROOF_SIGNATURE, &WYCHEPROOF_PUBLIC));
+
+        let mut wrong_recovery_id = WYCHEPROOF_SIGNATURE;
+        wrong_recovery_id[64] = 0;
+        assert!(!verify_ecdsa_prehashed(&hash, &wrong_recovery_id, &WYCHEPROOF_PUBLIC));
+        let mut tampered = WYCHEPROOF_SIGNATURE;
+        tampered[31] ^= 0x01;
+        assert!(!verify_ecdsa_prehashed(&hash, &tampered, &WYCHEPROOF_PUBLIC));
+    }
+
+    #[test]
+    fn classical_signatures_do_not_verify_as_post_quantum() {
+        let (message, public_key, signature) = ED25519_VECTORS[0];
+        for key_type in [
+            QuantumKeyType::LatticeBasedKEM,
+            QuantumKeyType::HashBased,
+            QuantumKeyType::Multivariate,
+            QuantumKeyType::Hybrid,
+        ] {
+            assert!(!verify(key_type, message, &signature, &public_key));
+        }
+    }
+
+    #[test]
+    fn dilithium_signatures() {
+        let keys = pqc_dilithium::Keypair::generate();
+        let message = b"JAM authorization";
+        let signature = keys.sign(message);
+
+        assert!(verify(QuantumKeyType::LatticeBasedKEM, message, &signature, &keys.public));
+        assert!(!verify(QuantumKeyType::LatticeBasedKEM, b"other", &signature, &keys.public));
+        assert!(!verify(QuantumKeyType::LatticeBasedKEM, message, &flip_last(&signature), &keys.public));
+        assert!(!verify(QuantumKeyType::LatticeBasedKEM, message, &signature, &keys.public[1..]));
+        assert!(!verify(QuantumKeyType::HashBased, message, &signature, &keys.public));
+    }
+
+    #[test]
+    fn dilithium_known_answer() {
+        let (message, signature, public_key) = (&KAT_MESSAGE[..], DILITHIUM_KAT_SIGNATURE, DILITHIUM_KAT_PUBLIC);
+        assert!(verify(QuantumKeyType::LatticeBasedKEM, message, signature, public_key));
+        assert!(!verify(QuantumKeyType::LatticeBasedKEM, &flip_last(message), signature, public_key));
+        assert!(!verify(QuantumKeyType::LatticeBasedKEM, message, &flip_first(signature), public_key));
+        assert!(!verify(QuantumKeyType::LatticeBasedKEM, message, &flip_last(signature), public_key));
+        assert!(!verify(QuantumKeyType::LatticeBasedKEM, message, signature, &flip_first(public_key)));
+        assert!(!verify(QuantumKeyType::LatticeBasedKEM, message, signature, &flip_last(public_key)));
+    }
+
+    #[test]
+    fn sphincs_known_answer() {
+        let (message, signature, public_key) = (&KAT_MESSAGE[..], SPHINCS_KAT_SIGNATURE, SPHINCS_KAT_PUBLIC);
+        assert!(verify(QuantumKeyType::HashBased, message, signature, public_key));
+        assert!(!verify(QuantumKeyType::HashBased, &flip_last(message), signature, public_key));
+        assert!(!verify(QuantumKeyType::HashBased, message, &flip_first(signature), public_key));
+        assert!(!verify(QuantumKeyType::HashBased, message, &flip_last(signature), public_key));
+        assert!(!verify(QuantumKeyType::HashBased, message, signature, &flip_first(public_key)));
+        assert!(!verify(QuantumKeyType::HashBased, message, signature, &flip_last(public_key)));
+    }
+
+    #[test]
+    fn sphincs_signatures() {
+        let (public_key, secret_key) = sphincs::keypair();
+        let message = b"JAM authorization";
+        let signature = sphincs::detached_sign(message, &secret_key);
+        let (public_key, signature) = (public_key.as_bytes(), signature.as_bytes());
+
+        assert!(verify(QuantumKeyType::HashBased, message, signature, public_key));
+        assert!(!verify(QuantumKeyType::HashBased, b"other", signature, public_key));
+        assert!(!verify(QuantumKeyType::HashBased, message, &flip_last(signature), public_key));
+        assert!(!verify(QuantumKeyType::HashBased, message, &signature[1..], public_key));
+        assert!(!verify(QuantumKeyType::LatticeBasedKEM, message, signature, public_key));
+    }
+
+    fn hybrid_pair() -> HybridPair {
+        HybridPair::new(ed25519::Pair::from_seed(&[0x11; 32]), pqc_dilithium::Keypair::generate())
+    }
+
+    #[test]
+    fn hybrid_encoding() {
+        let pair = hybrid_pair();
+        let public_key = pair.public();
+        let encoded = public_key.encode();
+        assert_eq!(&encoded[..32], &public_key.ed25519[..]);
+        assert_eq!(&encoded[32..34], &((pqc_dilithium::PUBLICKEYBYTES as u32) << 2 | 0b01).to_le_bytes()[..2]);
+        assert_eq!(&encoded[34..], &public_key.dilithium[..]);
+        assert_eq!(HybridPublicKey::from_quantum_pubkey(&encoded), Some(public_key.clone()));
+
+        let mut trailing = encoded.clone();
+        trailing.push(0);
+        assert_eq!(HybridPublicKey::from_quantum_pubkey(&trailing), None);
+        let short = HybridPublicKey { dilithium: public_key.dilithium[1..].to_vec(), ..public_key };
+        assert_eq!(HybridPublicKey::from_quantum_pubkey(&short.encode()), None);
+
+        let signature = pair.sign(b"JAM authorization");
+        let encoded = signature.encode();
+        assert_eq!(&encoded[..64], &signature.ed25519[..]);
+        assert_eq!(HybridSignature::decode_all(&mut &encoded[..]), Ok(signature));
+    }
+
+    #[test]
+    fn hybrid_signatures_need_both_halves() {
+        let pair = hybrid_pair();
+        let public_key = pair.public().encode();
+        let message = b"JAM authorization";
+        let signature = pair.sign(message);
+
+        assert!(verify(QuantumKeyType::Hybrid, message, &signature.encode(), &public_key));
+        assert!(!verify(QuantumKeyType::Hybrid, b"other", &signature.encode(), &public_key));
+
+        let mut classical_forged = signature.clone();
+        classical_forged.ed25519[0] ^= 0x01;
+        assert!(!verify(QuantumKeyType::Hybrid, message, &classical_forged.encode(), &public_key));
+
+        let post_quantum_forged = HybridSignature { dilithium: flip_last(&signature.dilithium), ..signature.clone() };
+        assert!(!verify(QuantumKeyType::Hybrid, message, &post_quantum_forged.encode(), &public_key));
+
+        let mut trailing = signature.encode();
+        trailing.push(0);
+        assert!(!verify(QuantumKeyType::Hybrid, message, &trailing, &public_key));
+    }
+
+    #[test]
+    fn hybrid_halves_do_not_verify_alone() {
+        let pair = hybrid_pair();
+        let public_key = pair.public();
+        let message = b"JAM authorization";
+        let signature = pair.sign(message);
+
+        assert!(!verify(QuantumKeyType::ECDSA, message, &signature.ed25519, &public_key.ed25519));
+        assert!(!verify(QuantumKeyType::LatticeBasedKEM, message, &signature.dilithium, &public_key.dilithium));
+        assert!(!verify(QuantumKeyType::ECDSA, message, &signature.encode(), &public_key.encode()));
+    }
+}
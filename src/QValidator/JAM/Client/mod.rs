@@ -2,12 +2,15 @@
 // Client implementation for IMRT
 
 use super::super::lib::*;
+use super::Authorizer::signature::HybridPair;
 use sp_std::prelude::*;
+use scale::Encode;
 use permaweb_lib::profile::{Profile, Zone, Wallet};
 
 // JAM Client
 pub struct JAMClient {
     actor: ActorX,
+    hybrid_signer: Option<HybridPair>,
 }
 
 impl JAMClient {
@@ -16,12 +19,29 @@ impl JAMClient {
         
         Self {
             actor,
+            hybrid_signer: None,
         }
     }
+
+    // Client signing `Hybrid` transactions with `signer`
+    pub fn with_hybrid_signer(signer: HybridPair) -> Self {
+        Self { hybrid_signer: Some(signer), ..Self::new() }
+    }
     
+    // `Hybrid` transactions are submitted with the SCALE-encoded composite
+    // signature, which the authorizer accepts only if both halves verify.
+    // The client holds no signing keys of other types.
     pub fn submit_transaction(&self, tx_data: &[u8], key_type: QuantumKeyType) -> Result<Vec<u8>, &'static str> {
-        // Implementation for transaction submission
-        Ok(Vec::new())
+        match key_type {
+            QuantumKeyType::Hybrid => {
+                let signer = self.hybrid_signer.as_ref().ok_or("No hybrid signing key")?;
+                Ok(signer.sign(tx_data).encode())
+            }
+            QuantumKeyType::ECDSA
+            | QuantumKeyType::LatticeBasedKEM
+            | QuantumKeyType::Multivariate
+            | QuantumKeyType::HashBased => Err("No signing key for key type"),
+        }
     }
     
     pub fn verify_transaction(&self, tx_hash: &[u8]) -> Result<bool, &'static str> {
@@ -2,6 +2,7 @@
 // Core component of Immortality Chain
 
 use sp_std::prelude::*;
+use scale::{Decode, Encode};
 use sp_runtime::{traits::{BlakeTwo256, Hash}, generic::Era};
 use frame_support::{traits::{Currency, ExistenceRequirement, Randomness}, weights::Weight};
 use frame_system::{self as system, ensure_signed};
@@ -12,14 +13,9 @@ const QUANTUM_SIGNATURE_SIZE: usize = 64;
 const CLASSICAL_SIGNATURE_SIZE: usize = 32;
 const BRIDGE_SIGNATURE_SIZE: usize = 48;
 
-// Quantum key types
-pub enum QuantumKeyType {
-    ECDSA,
-    LatticeBasedKEM,
-    Multivariate,
-    HashBased,
-    Hybrid,
-}
+// Quantum key types, as stored with each validator's key by the
+// QValidator pallet
+pub use quantum_validator::QuantumKeyType;
 
 // JAM types
 pub struct JAMHeader {